@@ -1,32 +1,92 @@
+use crate::file::merge::Strategy;
+use crate::registry::RegistryKind;
+use crate::user_config::UserConfig;
 use clap::{arg, command, Arg, ArgAction, ArgMatches, Command};
 use color_eyre::Result;
+use std::path::Path;
 
 pub fn build() -> Command {
     let new_command = Command::new("new")
         .about("Create a new project from a template")
         .arg(arg!([source] "The template source (URL or local name)").required(true))
-        .arg(arg!(-d --dir <DIRNAME> "The directory name of the new project"))
+        .arg(arg!([name] "The name of the new project").conflicts_with("dir"))
+        .arg(arg!(-d --dir <DIRNAME> "Deprecated: pass the project name as a positional argument"))
         .arg(
             arg!(-b --branch [BRANCH] "The branch to clone (if using a URL)")
                 .require_equals(true)
                 .num_args(0..=1)
                 .default_missing_value("INTERACTIVE"),
         )
-        .arg(
-            arg!(-m --mode <MODE> "The mode of the operation (if using a URL)")
-                .default_value("git"),
-        )
+        .arg(arg!(-m --mode <MODE> "The mode of the operation (if using a URL) [default: git]"))
         .arg(
             arg!(-f --force "Force the operation, overwriting existing files")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            arg!(--merge [STRATEGY] "Merge into a non-empty directory, resolving existing files by STRATEGY")
+                .require_equals(true)
+                .num_args(0..=1)
+                .default_missing_value("prompt")
+                .value_parser(Strategy::NAMES)
+                .conflicts_with("force"),
+        )
         .arg(
             Arg::new("keep-history")
                 .short('H')
                 .long("keep-history")
                 .help("Keep the history of the repository (if using a URL)")
                 .action(ArgAction::SetTrue),
-        );
+        )
+        .arg(
+            Arg::new("no-keep-history")
+                .long("no-keep-history")
+                .help("Do not keep the history of the repository, overriding `keep_history`")
+                .conflicts_with("keep-history")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(-x --exclude <GLOB> "Leave matching files out of the new project")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            arg!(--with <FEATURE> "Include files the template marks as optional")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("dry-run")
+                .short('n')
+                .long("dry-run")
+                .help("Show what would be generated without writing anything")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("reset-metadata")
+                .long("reset-metadata")
+                .help("Reset the version, description, repository and author in package.json")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("git-init")
+                .long("git-init")
+                .help("Initialize a git repository with an initial commit")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("no-git-init")
+                .long("no-git-init")
+                .help("Do not initialize a git repository, overriding `git.init`")
+                .conflicts_with("git-init")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"template-remote" <ACTION> "With --keep-history, rename the template's origin to 'template' or remove it [default: rename]")
+                .value_parser(["rename", "remove"]),
+        )
+        .arg(
+            arg!(--squash "With --keep-history, squash the template history into one commit")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(arg!(--remote <URL> "Add URL as the 'origin' remote of the new repository"));
 
     let clear_command = Command::new("clear")
         .about("Clear the cache")
@@ -45,21 +105,40 @@ pub fn build() -> Command {
 
     let add_command = Command::new("add")
         .about("Add a new template")
-        .arg(arg!([url] "the link to the source file").required(true))
+        .arg(arg!([url] "the link to the source file, or a local directory").required(true))
         .arg(arg!(-n --name <NAME> "the name of the template"))
         .arg(arg!(-d --description <DESCRIPTION> "the description of the template"))
-        .arg(arg!(-a --alias <ALIAS> "the alias of the template"))
+        .arg(
+            arg!(-a --alias <ALIAS> "an alias of the template (repeatable)")
+                .action(ArgAction::Append),
+        )
+        .arg(arg!(-r --ref <REF> "the branch or tag to pin the template to"))
+        .arg(
+            arg!(-t --tag <TAG> "a tag of the template; the first one is its category (repeatable)")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            arg!(-l --link "read a local directory live instead of copying a snapshot")
+                .action(ArgAction::SetTrue),
+        )
         .arg(arg!(-u --update "update the template").action(ArgAction::SetTrue))
         .arg(arg!(-f --force "force the operation").action(ArgAction::SetTrue));
 
-    let list_command = Command::new("list").about("List all templates");
+    let list_command = Command::new("list").about("List all templates").arg(
+        arg!(-t --tag <TAG> "Only list templates with this tag (repeatable)")
+            .action(ArgAction::Append),
+    );
+
+    let info_command = Command::new("info")
+        .about("Show details about a template")
+        .arg(arg!([name] "The name or alias of the template").required(true));
 
     let remove_command = Command::new("remove")
         .about("Remove one or more templates")
         .alias("rm")
         .alias("delete")
         .arg(
-            arg!([TEMPLATES] "Name(s) of the template(s) to remove")
+            arg!([TEMPLATES] "Name(s) of the template(s) to remove (interactive if omitted)")
                 .num_args(1..)
                 .conflicts_with("all"),
         )
@@ -103,27 +182,238 @@ pub fn build() -> Command {
         )
         .arg(arg!(-q --quiet "Suppress non-error output").action(ArgAction::SetTrue));
 
+    let edit_command = Command::new("edit")
+        .about("Edit the metadata of a template")
+        .arg(arg!([name] "The name or alias of the template").required(true))
+        .arg(arg!(-a --alias <ALIAS> "Add an alias (repeatable)").action(ArgAction::Append))
+        .arg(
+            Arg::new("remove-alias")
+                .long("remove-alias")
+                .value_name("ALIAS")
+                .help("Remove an alias (repeatable)")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("clear-aliases")
+                .long("clear-aliases")
+                .help("Remove all aliases before adding new ones")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(arg!(-t --tag <TAG> "Add a tag (repeatable)").action(ArgAction::Append))
+        .arg(
+            Arg::new("remove-tag")
+                .long("remove-tag")
+                .value_name("TAG")
+                .help("Remove a tag (repeatable)")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("clear-tags")
+                .long("clear-tags")
+                .help("Remove all tags before adding new ones")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(arg!(-d --description <DESCRIPTION> "The new description (empty to remove)"))
+        .arg(arg!(-u --url <URL> "The new source URL or local directory"))
+        .arg(arg!(-r --ref <REF> "The branch or tag to pin to (empty to remove)"));
+
+    let rename_command = Command::new("rename")
+        .about("Rename a template")
+        .alias("mv")
+        .arg(arg!([old] "The current name or alias of the template").required(true))
+        .arg(arg!([new] "The new name of the template").required(true));
+
+    let save_as_command = Command::new("save-as")
+        .about("Save a project directory as a new template")
+        .arg(arg!([name] "The name of the template").required(true))
+        .arg(arg!(--from <DIR> "The directory to capture").default_value("."))
+        .arg(arg!(-d --description <DESCRIPTION> "The description of the template"))
+        .arg(
+            arg!(-a --alias <ALIAS> "An alias of the template (repeatable)")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            arg!(-t --tag <TAG> "A tag of the template; the first one is its category (repeatable)")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            arg!(-p --placeholder "Replace the project's name with {{project_name}}")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(arg!(-f --force "Overwrite an existing template").action(ArgAction::SetTrue));
+
+    let doctor_command = Command::new("doctor")
+        .about("Diagnose problems with the installation and template registry")
+        .arg(
+            arg!(--fix "Repair the problems that can be fixed automatically")
+                .action(ArgAction::SetTrue),
+        );
+
+    let export_command = Command::new("export")
+        .about("Export the template registry to a TOML or JSON file")
+        .arg(arg!(-o --output <FILE> "Write to a file instead of stdout (.json for JSON)"));
+
+    let import_command = Command::new("import")
+        .about("Register templates from an exported registry file or URL")
+        .arg(arg!([source] "Path or URL of the registry file").required(true))
+        .arg(
+            Arg::new("on-conflict")
+                .long("on-conflict")
+                .value_name("STRATEGY")
+                .help("What to do when a template name is already registered")
+                .value_parser(["skip", "overwrite", "abort"])
+                .default_value("skip"),
+        )
+        .arg(
+            arg!(-c --clone "Clone templates now instead of on first use")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(arg!(-q --quiet "Suppress non-error output").action(ArgAction::SetTrue));
+
+    let registry_command = Command::new("registry")
+        .about("Manage remote shared template registries")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("add")
+                .about("Add a registry")
+                .arg(
+                    arg!([name] "The name used to namespace the registry's templates")
+                        .required(true),
+                )
+                .arg(
+                    arg!([url] "A git repository or HTTP URL serving a template.toml")
+                        .required(true),
+                )
+                .arg(
+                    arg!(--type <TYPE> "How to fetch the index [default: git for URLs ending in .git, http otherwise]")
+                        .value_parser(RegistryKind::NAMES),
+                )
+                .arg(
+                    arg!(--ttl <SECONDS> "How long the fetched index is cached")
+                        .value_parser(clap::value_parser!(u64)),
+                ),
+        )
+        .subcommand(
+            Command::new("remove")
+                .about("Remove a registry")
+                .alias("rm")
+                .arg(arg!([name] "The name of the registry").required(true)),
+        )
+        .subcommand(Command::new("list").about("List configured registries"))
+        .subcommand(
+            Command::new("sync")
+                .about("Fetch the latest index of one or all registries")
+                .arg(arg!([name] "The name of the registry")),
+        );
+
+    let undo_command =
+        Command::new("undo").about("Restore what the last destructive operation deleted");
+
+    let trash_command = Command::new("trash")
+        .about("Manage content kept from destructive operations")
+        .subcommand_required(true)
+        .subcommand(Command::new("list").about("List trash entries").alias("ls"))
+        .subcommand(
+            Command::new("restore")
+                .about("Restore a trash entry")
+                .arg(arg!([id] "The entry ID, as shown by 'ogito trash ls'").required(true)),
+        )
+        .subcommand(
+            Command::new("empty")
+                .about("Permanently delete every trash entry")
+                .arg(arg!(-f --force "Skip the confirmation").action(ArgAction::SetTrue)),
+        );
+
+    let config_command = Command::new("config")
+        .about("Manage the user configuration")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("get")
+                .about("Print a configuration value")
+                .arg(arg!([key] "The configuration key").required(true)),
+        )
+        .subcommand(
+            Command::new("set")
+                .about("Set a configuration value")
+                .arg(arg!([key] "The configuration key").required(true))
+                .arg(arg!([value] "The value to set").required(true)),
+        )
+        .subcommand(
+            Command::new("unset")
+                .about("Remove a configuration value")
+                .arg(arg!([key] "The configuration key").required(true)),
+        )
+        .subcommand(Command::new("list").about("List all configuration keys and values"))
+        .subcommand(Command::new("edit").about("Open the configuration file in your editor"));
+
     command!()
         .about("A lightweight CLI tool for managing and generating projects from custom templates")
         .subcommand(new_command)
         .subcommand(clear_command)
         .subcommand(add_command)
         .subcommand(list_command)
+        .subcommand(info_command)
         .subcommand(remove_command)
         .subcommand(update_command)
+        .subcommand(edit_command)
+        .subcommand(rename_command)
+        .subcommand(save_as_command)
+        .subcommand(doctor_command)
+        .subcommand(export_command)
+        .subcommand(import_command)
+        .subcommand(registry_command)
+        .subcommand(undo_command)
+        .subcommand(trash_command)
+        .subcommand(config_command)
+        .arg(
+            Arg::new("home")
+                .long("home")
+                .alias("cache-dir")
+                .value_name("DIR")
+                .help("Directory for the manifest, templates and cache [env: OGITO_HOME]")
+                .global(true),
+        )
         .subcommand_required(true)
         .arg_required_else_help(true)
 }
 
 pub async fn dispatch(matches: ArgMatches) -> Result<()> {
+    if let Some(home) = matches.get_one::<String>("home") {
+        crate::file::cache::set_home(Path::new(home));
+    }
+
+    // A broken config file must not prevent fixing it with `ogito config`
+    if let Ok(settings) = UserConfig::load() {
+        apply_settings(&settings);
+    }
+
     match matches.subcommand() {
         Some(("new", m)) => crate::cmd::new::run(m).await?,
         Some(("clear", m)) => crate::cmd::clear::run(m)?,
         Some(("add", m)) => crate::cmd::add::run(m).await?,
-        Some(("list", m)) => crate::cmd::list::run(m)?,
+        Some(("info", m)) => crate::cmd::info::run(m).await?,
+        Some(("list", m)) => crate::cmd::list::run(m).await?,
         Some(("remove", m)) => crate::cmd::remove::run(m)?,
         Some(("update", m)) => crate::cmd::update::run(m).await?,
+        Some(("edit", m)) => crate::cmd::edit::run(m)?,
+        Some(("rename", m)) => crate::cmd::rename::run(m)?,
+        Some(("save-as", m)) => crate::cmd::save_as::run(m).await?,
+        Some(("doctor", m)) => crate::cmd::doctor::run(m).await?,
+        Some(("export", m)) => crate::cmd::export::run(m)?,
+        Some(("import", m)) => crate::cmd::import::run(m).await?,
+        Some(("registry", m)) => crate::cmd::registry::run(m).await?,
+        Some(("undo", m)) => crate::cmd::undo::run(m)?,
+        Some(("trash", m)) => crate::cmd::trash::run(m)?,
+        Some(("config", m)) => crate::cmd::config::run(m)?,
         _ => {}
     }
     Ok(())
 }
+
+/// Applies the process-wide user settings.
+fn apply_settings(settings: &UserConfig) {
+    if settings.resolve_bool("ui.color") == Some(false) {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+}
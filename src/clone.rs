@@ -1,8 +1,10 @@
 use crate::file;
+use crate::file::staging::Staging;
 use crate::progress::create_spinner;
+use crate::trash;
 use crate::{
-    fetch::config::Config,
-    file::cache::CacheMetadata,
+    fetch::{config::Config, network::Network},
+    file::{cache::CacheMetadata, exclude::ExcludeRules},
     git::get_remote_refs,
     mode::{Mode, Site},
     regex::{extract_host, extract_path, is_valid_url},
@@ -24,9 +26,27 @@ pub async fn clone(url: &str, config: &Config<'_>) -> Result<()> {
     Ok(())
 }
 
-pub async fn force_clone(url: &str, dir: &str, config: &Config<'_>) -> Result<()> {
-    fs::remove_dir_all(dir)?;
-    clone(url, config).await?;
+/// Replaces `dir` with a fresh clone, keeping its previous content in the
+/// trash under `operation`. `dir` is left untouched if the clone fails.
+pub async fn force_clone(url: &str, dir: &str, config: &Config<'_>, operation: &str) -> Result<()> {
+    let staging = Staging::new(Path::new(dir))?;
+    let staged = staging
+        .path()
+        .to_str()
+        .ok_or_else(|| eyre!("Invalid directory name: contains non-UTF-8 characters"))?
+        .to_string();
+    let staged_config = Config {
+        dir: &staged,
+        mode: config.mode.clone(),
+        force: config.force,
+        keep_history: config.keep_history,
+        branch: config.branch,
+        exclude: config.exclude.clone(),
+    };
+    clone(url, &staged_config).await?;
+    if let Some(entry) = staging.commit(operation)? {
+        trash::print_undo_hint(&entry);
+    }
     Ok(())
 }
 
@@ -41,6 +61,7 @@ fn git_clone(url: &str, config: &Config) -> Result<()> {
 
     let dir_path = Path::new(config.dir);
     let mut builder = RepoBuilder::new();
+    builder.fetch_options(Network::from_settings()?.fetch_options(url));
 
     let status = match config.branch {
         Some(branch) => {
@@ -88,6 +109,10 @@ fn git_clone(url: &str, config: &Config) -> Result<()> {
         fs::remove_dir_all(git_dir)?;
     }
 
+    if let Some(exclude) = &config.exclude {
+        ExcludeRules::load(dir_path, exclude)?.prune(dir_path)?;
+    }
+
     Ok(())
 }
 
@@ -172,7 +197,12 @@ async fn tar_clone(url: &str, config: &Config<'_>) -> Result<()> {
     let temp_file = file::download_file(&archive_url, &cache_metadata).await?;
 
     pb.set_message("🗜️ Extracting archive...");
-    file::extract_archive(&temp_file, dir)?;
+    file::extract_archive(
+        &temp_file,
+        dir,
+        config.exclude.as_ref(),
+        &file::ExtractLimits::from_settings(),
+    )?;
 
     pb.finish_and_clear();
     Ok(())
@@ -1,71 +1,190 @@
 use crate::{
-    file::cache::get_cache_root,
-    manifest::{ManifestFile, Template},
+    file::{
+        cache::{get_manifest_path, get_templates_dir},
+        copy::copy_filtered,
+        placeholder::insert_placeholder,
+    },
+    manifest::{normalize_tags, ManifestFile, Template},
     mode::Mode,
     regex::{extract_path, is_valid_url},
+    trash,
 };
 use clap::ArgMatches;
 use color_eyre::{eyre::eyre, Result};
-use std::path::Path;
+use console::style;
+use std::{fs, path::Path};
 
 pub async fn run(matches: &ArgMatches) -> Result<()> {
     let url = matches
         .get_one::<String>("url")
         .ok_or_else(|| eyre!("URL is required"))?;
 
-    if !is_valid_url(url)? {
-        return Err(eyre!("Invalid URL: {}", url));
+    let local_dir = if is_valid_url(url)? {
+        None
+    } else if Path::new(url).is_dir() {
+        Some(fs::canonicalize(url)?)
+    } else {
+        return Err(eyre!(
+            "Invalid source: '{}' is neither a supported URL nor a directory",
+            url
+        ));
+    };
+
+    let link = matches.get_flag("link");
+    if link && local_dir.is_none() {
+        return Err(eyre!("--link can only be used with a local directory"));
     }
 
-    let name = if let Some(name) = matches.get_one::<String>("name") {
-        name.clone()
-    } else {
-        generate_default_name(url)?
+    let name = match (matches.get_one::<String>("name"), &local_dir) {
+        (Some(name), _) => name.clone(),
+        (None, Some(dir)) => dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(String::from)
+            .ok_or_else(|| eyre!("Could not derive a name from '{}'", url))?,
+        (None, None) => generate_default_name(url)?,
     };
 
-    if name.contains("..") || Path::new(&name).is_absolute() {
+    validate_name(&name)?;
+
+    let source = match &local_dir {
+        Some(dir) => dir
+            .to_str()
+            .ok_or_else(|| eyre!("Invalid path: contains non-UTF-8 characters"))?
+            .to_string(),
+        None => url.clone(),
+    };
+
+    let force = matches.get_flag("force");
+    let template = build_template(matches, &source)?;
+    for alias in &template.aliases {
+        validate_alias(alias)?;
+    }
+
+    let config_path = get_manifest_path()?;
+    let mut config = ManifestFile::load(&config_path)?;
+    if config.content.templates.contains_key(&name) && !force {
         return Err(eyre!(
-            "Invalid template name: '{}'. It cannot be an absolute path or contain '..'",
+            "⚠️ {} already exists. Use --force to overwrite it.",
             name
         ));
     }
+    config.content.check_conflicts(&name, &template)?;
 
-    let force = matches.get_flag("force");
-    let template = build_template(matches, url);
-    let templates_dir = get_cache_root().join("templates");
-    let destination = templates_dir.join(&name);
+    fetch_template(&name, &template, force).await?;
+    config.add_template(name.clone(), template.clone());
+    config.save()?;
+
+    if template.link {
+        println!(
+            "{} Linked template '{}' to {}",
+            style("🔗").cyan(),
+            name,
+            template.url
+        );
+    }
+
+    Ok(())
+}
+
+/// Stores a template's source under `templates/<name>`: remote templates are
+/// cloned, local directories are snapshot-copied and linked ones are left alone.
+pub async fn fetch_template(name: &str, template: &Template, force: bool) -> Result<()> {
+    if template.link {
+        return Ok(());
+    }
+
+    let templates_dir = get_templates_dir()?;
+    let destination = templates_dir.join(name);
 
     if destination.exists() && !force {
         return Err(eyre!(format!(
             "⚠️ {} already exists. Use --force to overwrite it.",
-            &name
+            name
         )));
     }
 
+    if template.is_local() {
+        if destination.exists() {
+            let entry = trash::discard(&format!("add {name}"), &destination)?;
+            trash::print_undo_hint(&entry);
+        }
+        copy_filtered(Path::new(&template.url), &destination)?;
+        if let Some(project_name) = &template.project_name {
+            insert_placeholder(&destination, project_name)?;
+        }
+        return Ok(());
+    }
+
     let dest_string = destination
         .to_str()
         .ok_or_else(|| eyre!("Failed to convert destination path to string"))?
         .to_string();
 
-    let clone_config =
-        crate::fetch::config::Config::from(&dest_string, Mode::Git, force, true, None);
+    let clone_config = crate::fetch::config::Config::from(
+        &dest_string,
+        Mode::Git,
+        force,
+        true,
+        template.git_ref.as_ref(),
+    );
 
-    crate::clone::clone(&template.url, &clone_config).await?;
+    if destination.exists() {
+        crate::clone::force_clone(
+            &template.url,
+            &dest_string,
+            &clone_config,
+            &format!("add {name}"),
+        )
+        .await?;
+    } else {
+        crate::clone::clone(&template.url, &clone_config).await?;
+    }
 
-    let config_path = get_cache_root().join("template.toml");
-    let mut config = ManifestFile::load(&config_path)?;
-    config.add_template(name.clone(), template.clone());
-    config.save()?;
+    Ok(())
+}
 
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.contains("..") || Path::new(name).is_absolute() {
+        return Err(eyre!(
+            "Invalid template name: '{}'. It cannot be an absolute path or contain '..'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Checks an alias: a short name that is not a path and does not look like a
+/// registry template, which are written `@<registry>/<name>`.
+pub fn validate_alias(alias: &str) -> Result<()> {
+    if alias.is_empty()
+        || alias.starts_with('@')
+        || alias.contains(['/', '\\'])
+        || alias.contains("..")
+        || alias.chars().any(char::is_whitespace)
+    {
+        return Err(eyre!(
+            "Invalid alias: '{}'. It cannot be empty, start with '@', or contain '/', '..' or spaces",
+            alias
+        ));
+    }
     Ok(())
 }
 
-fn build_template(matches: &ArgMatches, url: &str) -> Template {
-    Template {
+fn build_template(matches: &ArgMatches, url: &str) -> Result<Template> {
+    Ok(Template {
         description: matches.get_one::<String>("description").cloned(),
-        alias: matches.get_one::<String>("alias").cloned(),
+        aliases: matches
+            .get_many::<String>("alias")
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
         url: url.to_owned(),
-    }
+        git_ref: matches.get_one::<String>("ref").cloned(),
+        link: matches.get_flag("link"),
+        project_name: None,
+        tags: normalize_tags(matches.get_many::<String>("tag").unwrap_or_default())?,
+    })
 }
 
 fn generate_default_name(url: &str) -> Result<String> {
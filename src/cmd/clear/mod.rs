@@ -1,6 +1,6 @@
 pub mod dir;
 
-use crate::file::clear_directory;
+use crate::file::{cache::get_cache_dir, clear_directory};
 use clap::ArgMatches;
 use color_eyre::Result;
 use dialoguer::Confirm;
@@ -14,10 +14,7 @@ pub fn run(matches: &ArgMatches) -> Result<()> {
     let dry_run = matches.get_flag("dry-run");
     let verbose = matches.get_flag("verbose");
 
-    let cache_path = dirs::cache_dir()
-        .expect("Failed to get cache directory")
-        .join("ogito")
-        .join("cache");
+    let cache_path = get_cache_dir()?;
 
     if !cache_path.exists() {
         println!("📦 Cache directory not found: {}", cache_path.display());
@@ -0,0 +1,90 @@
+use crate::user_config::{env_var_name, get_config_path, UserConfig, KEYS};
+use clap::ArgMatches;
+use color_eyre::{eyre::eyre, Result};
+use comfy_table::{Cell, ContentArrangement, Table};
+use console::style;
+use std::process::Command;
+
+pub fn run(matches: &ArgMatches) -> Result<()> {
+    let config_path = get_config_path()?;
+    let mut config = UserConfig::load_from(&config_path)?;
+
+    match matches.subcommand() {
+        Some(("get", m)) => {
+            let key = m
+                .get_one::<String>("key")
+                .ok_or_else(|| eyre!("Key is required"))?;
+            match config.get(key)? {
+                Some(value) => println!("{value}"),
+                None => return Err(eyre!("'{}' is not set", key)),
+            }
+        }
+        Some(("set", m)) => {
+            let key = m
+                .get_one::<String>("key")
+                .ok_or_else(|| eyre!("Key is required"))?;
+            let value = m
+                .get_one::<String>("value")
+                .ok_or_else(|| eyre!("Value is required"))?;
+            config.set(key, value)?;
+            config.save_to(&config_path)?;
+            println!("{} {} = {}", style("✅").green(), key, value);
+        }
+        Some(("unset", m)) => {
+            let key = m
+                .get_one::<String>("key")
+                .ok_or_else(|| eyre!("Key is required"))?;
+            config.unset(key)?;
+            config.save_to(&config_path)?;
+            println!("{} {} unset", style("✅").green(), key);
+        }
+        Some(("list", _)) => {
+            let mut table = Table::new();
+            table.set_header(vec!["Key", "Value", "Source", "Description"]);
+            for (key, description) in KEYS {
+                let env_name = env_var_name(key);
+                let (value, source) = match std::env::var(&env_name) {
+                    Ok(value) if !value.is_empty() => (value, env_name),
+                    _ => match config.get(key)? {
+                        Some(value) => (value, "config".to_string()),
+                        None => ("-".to_string(), "default".to_string()),
+                    },
+                };
+                table.add_row(vec![
+                    Cell::new(key),
+                    Cell::new(value),
+                    Cell::new(source),
+                    Cell::new(description),
+                ]);
+            }
+            table.set_content_arrangement(ContentArrangement::Dynamic);
+            println!("{table}");
+            println!("Config file: {}", config_path.display());
+        }
+        Some(("edit", _)) => {
+            if !config_path.exists() {
+                config.save_to(&config_path)?;
+            }
+            let editor = std::env::var("VISUAL")
+                .or_else(|_| std::env::var("EDITOR"))
+                .unwrap_or_else(|_| {
+                    if cfg!(windows) {
+                        "notepad".to_string()
+                    } else {
+                        "vi".to_string()
+                    }
+                });
+            let status = Command::new(&editor)
+                .arg(&config_path)
+                .status()
+                .map_err(|e| eyre!("Failed to launch editor '{}': {}", editor, e))?;
+            if !status.success() {
+                return Err(eyre!("Editor '{}' exited with {}", editor, status));
+            }
+            UserConfig::load_from(&config_path)?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
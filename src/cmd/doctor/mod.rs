@@ -0,0 +1,474 @@
+use crate::cmd::add::fetch_template;
+use crate::file::cache::{get_cache_dir, get_manifest_path, get_templates_dir};
+use crate::git::Git;
+use crate::manifest::{backup_path, check_schema_version, restore_backup, Manifest};
+use crate::trash::{print_undo_hint, Trash, TrashEntry};
+use crate::user_config::{get_config_path, UserConfig};
+use clap::ArgMatches;
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use flate2::read::GzDecoder;
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use tar::Archive;
+
+enum Level {
+    Ok,
+    Warning,
+    Error,
+}
+
+enum Fix {
+    RemoveDir(PathBuf),
+    Fetch(String),
+    RestoreManifest(PathBuf),
+}
+
+struct Finding {
+    level: Level,
+    message: String,
+    hint: Option<String>,
+    fix: Option<Fix>,
+}
+
+impl Finding {
+    fn ok(message: impl Into<String>) -> Self {
+        Self {
+            level: Level::Ok,
+            message: message.into(),
+            hint: None,
+            fix: None,
+        }
+    }
+
+    fn problem(level: Level, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            level,
+            message: message.into(),
+            hint: Some(hint.into()),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+pub async fn run(matches: &ArgMatches) -> Result<()> {
+    let apply_fixes = matches.get_flag("fix");
+
+    let mut findings = vec![check_git()];
+    let manifest = check_manifest(&mut findings)?;
+    if let Some(manifest) = &manifest {
+        check_templates(manifest, &mut findings)?;
+    }
+    check_cache(&mut findings)?;
+    check_network(&mut findings);
+    check_credentials(&mut findings);
+
+    let mut problems = 0;
+    let mut trashed = None;
+    for finding in findings {
+        let icon = match finding.level {
+            Level::Ok => style("✅").green(),
+            Level::Warning => style("⚠️").yellow(),
+            Level::Error => style("❌").red(),
+        };
+        println!("{} {}", icon, finding.message);
+
+        if matches!(finding.level, Level::Ok) {
+            continue;
+        }
+
+        match (&finding.fix, apply_fixes) {
+            (Some(fix), true) => match apply(fix, &mut trashed).await {
+                Ok(()) => println!("   {} fixed", style("↳").green()),
+                Err(e) => {
+                    problems += 1;
+                    println!("   {} fix failed: {}", style("↳").red(), e);
+                }
+            },
+            _ => {
+                problems += 1;
+                if let Some(hint) = &finding.hint {
+                    println!("   {} {}", style("↳").dim(), hint);
+                }
+            }
+        }
+    }
+
+    if let Some(entry) = &trashed {
+        print_undo_hint(entry);
+    }
+    if problems > 0 {
+        return Err(eyre!("doctor found {} problem(s)", problems));
+    }
+    println!("{} Everything looks good!", style("✨").cyan());
+    Ok(())
+}
+
+/// Applies `fix`, moving removed directories to the `trashed` entry shared by
+/// the run.
+async fn apply(fix: &Fix, trashed: &mut Option<TrashEntry>) -> Result<()> {
+    match fix {
+        Fix::RemoveDir(path) => {
+            let entry = match trashed {
+                Some(entry) => entry,
+                None => trashed.insert(Trash::open()?.create_entry("doctor --fix")?),
+            };
+            entry.store(path)?;
+        }
+        Fix::Fetch(name) => {
+            let manifest = Manifest::read(&get_manifest_path()?)?;
+            let template = manifest
+                .templates
+                .get(name)
+                .ok_or_else(|| eyre!("Template '{}' not found", name))?;
+            fetch_template(name, template, true).await?;
+        }
+        Fix::RestoreManifest(path) => {
+            let corrupt = restore_backup(path)?;
+            println!("   The corrupt manifest was kept at {}", corrupt.display());
+        }
+    }
+    Ok(())
+}
+
+fn check_git() -> Finding {
+    match Git::new().version() {
+        Ok(output) if output.status.success() => Finding::ok(format!(
+            "{} found",
+            String::from_utf8_lossy(&output.stdout).trim()
+        )),
+        _ => Finding::problem(
+            Level::Error,
+            "git was not found in PATH",
+            "install git and make sure it is on your PATH; it is needed to list branches and tags",
+        ),
+    }
+}
+
+fn check_manifest(findings: &mut Vec<Finding>) -> Result<Option<Manifest>> {
+    let path = get_manifest_path()?;
+    if !path.exists() {
+        findings.push(Finding::ok(format!(
+            "No manifest yet at {} (created by 'ogito add')",
+            path.display()
+        )));
+        return Ok(Some(Manifest::default()));
+    }
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => {
+            findings.push(Finding::problem(
+                Level::Error,
+                format!("Cannot read {}: {}", path.display(), e),
+                "check the file permissions",
+            ));
+            return Ok(None);
+        }
+    };
+
+    let version = content.parse::<toml::Table>().ok().map(|table| {
+        check_schema_version(table.get("schema_version").and_then(|v| v.as_integer()))
+    });
+    if let Some(Err(e)) = version {
+        findings.push(Finding::problem(
+            Level::Error,
+            e.to_string(),
+            "install a newer release of ogito",
+        ));
+        return Ok(None);
+    }
+
+    match Manifest::from_toml(&content) {
+        Ok(manifest) => {
+            findings.push(Finding::ok(format!(
+                "Manifest is valid ({} template(s))",
+                manifest.templates.len()
+            )));
+            Ok(Some(manifest))
+        }
+        Err(e) => {
+            let message = format!(
+                "Manifest {} is invalid: {}",
+                path.display(),
+                e.to_string().lines().next().unwrap_or_default()
+            );
+            findings.push(if backup_path(&path).is_file() {
+                Finding::problem(
+                    Level::Error,
+                    message,
+                    "rerun with --fix to restore the last good version",
+                )
+                .with_fix(Fix::RestoreManifest(path))
+            } else {
+                Finding::problem(
+                    Level::Error,
+                    message,
+                    "fix the file by hand, or move it aside to start over",
+                )
+            });
+            Ok(None)
+        }
+    }
+}
+
+fn check_templates(manifest: &Manifest, findings: &mut Vec<Finding>) -> Result<()> {
+    let templates_dir = get_templates_dir()?;
+    let mut healthy = true;
+
+    for (name, template) in &manifest.templates {
+        let source = template.source_dir(name, &templates_dir);
+        if source.is_dir() {
+            continue;
+        }
+        healthy = false;
+        if template.link {
+            findings.push(Finding::problem(
+                Level::Error,
+                format!(
+                    "Linked template '{}' points to a missing directory: {}",
+                    name,
+                    source.display()
+                ),
+                format!("run 'ogito edit {name} --url <dir>' or 'ogito remove {name}'"),
+            ));
+        } else {
+            findings.push(
+                Finding::problem(
+                    Level::Warning,
+                    format!("Template '{name}' has no local copy"),
+                    format!("run 'ogito update {name}' or rerun with --fix"),
+                )
+                .with_fix(Fix::Fetch(name.clone())),
+            );
+        }
+    }
+
+    let mut known: BTreeSet<String> = manifest.templates.keys().cloned().collect();
+    known.extend(manifest.registries.keys().map(|name| format!("@{name}")));
+    for orphan in find_orphans(&templates_dir, &known)? {
+        healthy = false;
+        findings.push(
+            Finding::problem(
+                Level::Warning,
+                format!("Orphan directory not in the manifest: {}", orphan.display()),
+                "register it with 'ogito add <dir>', or rerun with --fix to move it to the trash",
+            )
+            .with_fix(Fix::RemoveDir(orphan)),
+        );
+    }
+
+    if healthy {
+        findings.push(Finding::ok("All templates have a matching directory"));
+    }
+    Ok(())
+}
+
+/// Returns directories under `templates_dir` that do not belong to any of the
+/// `known` template names. Names may span several path components.
+fn find_orphans(templates_dir: &Path, known: &BTreeSet<String>) -> Result<Vec<PathBuf>> {
+    let mut orphans = Vec::new();
+    if templates_dir.is_dir() {
+        collect_orphans(templates_dir, "", known, &mut orphans)?;
+    }
+    Ok(orphans)
+}
+
+fn collect_orphans(
+    dir: &Path,
+    prefix: &str,
+    known: &BTreeSet<String>,
+    orphans: &mut Vec<PathBuf>,
+) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        if known.contains(&name) {
+            continue;
+        }
+        let nested = format!("{name}/");
+        if known.iter().any(|known| known.starts_with(&nested)) {
+            collect_orphans(&entry.path(), &nested, known, orphans)?;
+        } else {
+            orphans.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+fn check_cache(findings: &mut Vec<Finding>) -> Result<()> {
+    let cache_dir = get_cache_dir()?;
+    let mut bad = Vec::new();
+    if cache_dir.is_dir() {
+        collect_bad_cache_entries(&cache_dir, 0, &mut bad)?;
+    }
+
+    if bad.is_empty() {
+        findings.push(Finding::ok("Archive cache is healthy"));
+    }
+    for entry in bad {
+        findings.push(
+            Finding::problem(
+                Level::Warning,
+                format!("Broken cache entry: {}", entry.display()),
+                "run 'ogito clear' or rerun with --fix",
+            )
+            .with_fix(Fix::RemoveDir(entry)),
+        );
+    }
+    Ok(())
+}
+
+/// Cache entries live at `<owner>/<repo>/<hash[..2]>/<hash[2..]>/archive.tar.gz`.
+fn collect_bad_cache_entries(dir: &Path, depth: usize, bad: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if depth < 3 {
+            collect_bad_cache_entries(&path, depth + 1, bad)?;
+        } else if !is_valid_archive(&path.join("archive.tar.gz")) {
+            bad.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn is_valid_archive(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let mut archive = Archive::new(GzDecoder::new(file));
+    match archive.entries() {
+        Ok(entries) => entries.into_iter().all(|entry| entry.is_ok()),
+        Err(_) => false,
+    }
+}
+
+fn check_network(findings: &mut Vec<Finding>) {
+    let settings = UserConfig::load().unwrap_or_default();
+    let proxy = ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+        .or_else(|| settings.resolve("proxy"));
+    match proxy {
+        Some(proxy) if !proxy.contains("://") => findings.push(Finding::problem(
+            Level::Warning,
+            format!("Proxy '{proxy}' has no scheme"),
+            "use a full URL such as http://proxy.example.com:8080",
+        )),
+        Some(proxy) => findings.push(Finding::ok(format!("Using proxy {proxy}"))),
+        None => findings.push(Finding::ok("No proxy configured")),
+    }
+
+    for var in ["SSL_CERT_FILE", "SSL_CERT_DIR"] {
+        if let Some(value) = std::env::var_os(var) {
+            if !Path::new(&value).exists() {
+                findings.push(Finding::problem(
+                    Level::Error,
+                    format!(
+                        "{} points to a missing path: {}",
+                        var,
+                        value.to_string_lossy()
+                    ),
+                    format!("unset {var} or point it to your certificate bundle"),
+                ));
+            }
+        }
+    }
+}
+
+fn check_credentials(findings: &mut Vec<Finding>) {
+    let settings = match UserConfig::load() {
+        Ok(settings) => settings,
+        Err(e) => {
+            let hint = match get_config_path() {
+                Ok(path) => format!("fix {} or run 'ogito config edit'", path.display()),
+                Err(_) => "run 'ogito config edit'".to_string(),
+            };
+            findings.push(Finding::problem(Level::Error, e.to_string(), hint));
+            return;
+        }
+    };
+
+    if let Some(tokens_file) = settings.resolve("tokens_file") {
+        if !Path::new(&tokens_file).is_file() {
+            findings.push(Finding::problem(
+                Level::Error,
+                format!("Tokens file not found: {tokens_file}"),
+                "create it or run 'ogito config unset tokens_file'",
+            ));
+            return;
+        }
+        findings.push(Finding::ok(format!("Tokens file found: {tokens_file}")));
+    }
+
+    let tokens: Vec<&str> = ["GITHUB_TOKEN", "GITLAB_TOKEN"]
+        .into_iter()
+        .filter(|var| std::env::var_os(var).is_some_and(|v| !v.is_empty()))
+        .collect();
+    if tokens.is_empty() {
+        findings.push(Finding::ok(
+            "No access tokens set (only public templates are reachable over HTTPS)",
+        ));
+    } else {
+        findings.push(Finding::ok(format!(
+            "Access tokens set: {}",
+            tokens.join(", ")
+        )));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_find_orphans() -> Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        for path in [
+            "react",
+            "owner/repo",
+            "owner/stale",
+            "leftover",
+            "@acme/tool",
+        ] {
+            fs::create_dir_all(root.join(path))?;
+        }
+
+        let known: BTreeSet<String> = ["react", "owner/repo", "@acme"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let mut orphans = find_orphans(root, &known)?;
+        orphans.sort();
+
+        assert_eq!(
+            orphans,
+            vec![root.join("leftover"), root.join("owner/stale")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_valid_archive() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("archive.tar.gz");
+        fs::write(&path, b"not an archive")?;
+        assert!(!is_valid_archive(&path));
+        assert!(!is_valid_archive(&dir.path().join("missing.tar.gz")));
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use crate::cmd::add::validate_alias;
+use crate::file::cache::{get_manifest_path, get_templates_dir};
+use crate::manifest::{normalize_tags, ManifestFile, Template};
+use crate::regex::is_valid_url;
+use crate::trash;
+use clap::ArgMatches;
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use std::{fs, path::Path};
+
+pub fn run(matches: &ArgMatches) -> Result<()> {
+    let name = matches
+        .get_one::<String>("name")
+        .ok_or_else(|| eyre!("Template name is required"))?;
+
+    let config_path = get_manifest_path()?;
+    let mut config = ManifestFile::load(&config_path)?;
+    let actual_name = config
+        .content
+        .find(name)
+        .cloned()
+        .ok_or_else(|| eyre!("Template '{}' not found", name))?;
+    let mut template = config.content.templates[&actual_name].clone();
+
+    // An empty value clears an optional field
+    let optional = |value: &String| (!value.is_empty()).then(|| value.clone());
+    if matches.get_flag("clear-aliases") {
+        template.aliases.clear();
+    }
+    for alias in matches
+        .get_many::<String>("remove-alias")
+        .unwrap_or_default()
+    {
+        template.aliases.retain(|a| a != alias);
+    }
+    for alias in matches.get_many::<String>("alias").unwrap_or_default() {
+        validate_alias(alias)?;
+        if !template.aliases.contains(alias) {
+            template.aliases.push(alias.clone());
+        }
+    }
+    if matches.get_flag("clear-tags") {
+        template.tags.clear();
+    }
+    let removed = normalize_tags(matches.get_many::<String>("remove-tag").unwrap_or_default())?;
+    template.tags.retain(|tag| !removed.contains(tag));
+    let added = normalize_tags(matches.get_many::<String>("tag").unwrap_or_default())?;
+    template.tags = normalize_tags(template.tags.iter().chain(&added))?;
+    if let Some(description) = matches.get_one::<String>("description") {
+        template.description = optional(description);
+    }
+    if let Some(git_ref) = matches.get_one::<String>("ref") {
+        template.git_ref = optional(git_ref);
+    }
+    if let Some(url) = matches.get_one::<String>("url") {
+        set_source(&mut template, url)?;
+    }
+
+    let previous = &config.content.templates[&actual_name];
+    if *previous == template {
+        println!("Nothing to change for template '{actual_name}'.");
+        return Ok(());
+    }
+
+    config.content.check_conflicts(&actual_name, &template)?;
+
+    // The cached copy no longer matches its source; `ogito new` fetches it again
+    let source_changed = previous.url != template.url || previous.git_ref != template.git_ref;
+    let mut replaced = None;
+    if source_changed {
+        let cached = get_templates_dir()?.join(&actual_name);
+        if cached.exists() {
+            replaced = Some(trash::discard(&format!("edit {actual_name}"), &cached)?);
+        }
+    }
+
+    config.add_template(actual_name.clone(), template);
+    config.save()?;
+
+    println!("{} Updated template '{}'", style("✅").green(), actual_name);
+    if let Some(entry) = &replaced {
+        trash::print_undo_hint(entry);
+    }
+    Ok(())
+}
+
+/// Points `template` at `url`. A repository URL is cloned, and a directory is
+/// linked or snapshot as the template already was.
+fn set_source(template: &mut Template, url: &str) -> Result<()> {
+    if is_valid_url(url)? {
+        template.url = url.to_string();
+        template.link = false;
+    } else if Path::new(url).is_dir() {
+        template.url = fs::canonicalize(url)?
+            .to_str()
+            .ok_or_else(|| eyre!("Invalid path: contains non-UTF-8 characters"))?
+            .to_string();
+    } else {
+        return Err(eyre!(
+            "Invalid source: '{}' is neither a supported URL nor a directory",
+            url
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_set_source_updates_link() -> Result<()> {
+        let dir = tempdir()?;
+        let mut template = Template {
+            description: None,
+            aliases: Vec::new(),
+            url: dir.path().to_str().unwrap().to_string(),
+            git_ref: None,
+            link: true,
+            project_name: None,
+            tags: Vec::new(),
+        };
+
+        set_source(&mut template, "https://github.com/owner/repo")?;
+        assert_eq!(template.url, "https://github.com/owner/repo");
+        assert!(!template.link);
+
+        set_source(&mut template, dir.path().to_str().unwrap())?;
+        assert_eq!(Path::new(&template.url), fs::canonicalize(dir.path())?);
+        assert!(!template.link);
+
+        assert!(set_source(&mut template, "not/a/dir").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_source_keeps_snapshot_and_link() -> Result<()> {
+        let (old, new) = (tempdir()?, tempdir()?);
+        let new_path = new.path().to_str().unwrap();
+        let mut snapshot = Template {
+            description: None,
+            aliases: Vec::new(),
+            url: old.path().to_str().unwrap().to_string(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags: Vec::new(),
+        };
+        let mut linked = Template {
+            link: true,
+            ..snapshot.clone()
+        };
+
+        set_source(&mut snapshot, new_path)?;
+        assert_eq!(Path::new(&snapshot.url), fs::canonicalize(new.path())?);
+        assert!(!snapshot.link);
+
+        set_source(&mut linked, new_path)?;
+        assert!(linked.link);
+        Ok(())
+    }
+}
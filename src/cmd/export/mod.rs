@@ -0,0 +1,39 @@
+use crate::file::cache::get_manifest_path;
+use crate::manifest::Manifest;
+use clap::ArgMatches;
+use color_eyre::Result;
+use console::style;
+use std::fs;
+use std::path::Path;
+
+pub fn run(matches: &ArgMatches) -> Result<()> {
+    let config_path = get_manifest_path()?;
+    let manifest = Manifest::read(&config_path)?;
+
+    match matches.get_one::<String>("output") {
+        Some(output) => {
+            let output = Path::new(output);
+            fs::write(output, serialize(&manifest, output)?)?;
+            println!(
+                "{} Exported {} template(s) to {}",
+                style("✅").green(),
+                manifest.templates.len(),
+                output.display()
+            );
+        }
+        None => print!("{}", toml::to_string_pretty(&manifest)?),
+    }
+
+    Ok(())
+}
+
+fn serialize(manifest: &Manifest, output: &Path) -> Result<String> {
+    let is_json = output
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    if is_json {
+        Ok(serde_json::to_string_pretty(manifest)? + "\n")
+    } else {
+        Ok(toml::to_string_pretty(manifest)?)
+    }
+}
@@ -0,0 +1,283 @@
+use crate::cmd::add::{fetch_template, validate_alias, validate_name};
+use crate::fetch::network::Network;
+use crate::file::cache::{get_manifest_path, get_templates_dir};
+use crate::manifest::{check_schema_version, Manifest, ManifestFile};
+use crate::regex::is_valid_url;
+use crate::trash::{print_undo_hint, Trash, TrashEntry};
+use clap::ArgMatches;
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use std::fs;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Strategy {
+    Skip,
+    Overwrite,
+    Abort,
+}
+
+impl From<&String> for Strategy {
+    fn from(value: &String) -> Strategy {
+        match value.as_str() {
+            "overwrite" => Strategy::Overwrite,
+            "abort" => Strategy::Abort,
+            _ => Strategy::Skip,
+        }
+    }
+}
+
+pub async fn run(matches: &ArgMatches) -> Result<()> {
+    let source = matches
+        .get_one::<String>("source")
+        .ok_or_else(|| eyre!("Source is required"))?;
+    let strategy: Strategy = matches
+        .get_one::<String>("on-conflict")
+        .map(Strategy::from)
+        .unwrap_or(Strategy::Skip);
+    let clone_now = matches.get_flag("clone");
+    let quiet = matches.get_flag("quiet");
+
+    let content = read_source(source).await?;
+    let incoming = parse(&content)?;
+    validate(&incoming, is_remote(source))?;
+
+    let config_path = get_manifest_path()?;
+    let mut config = ManifestFile::load(&config_path)?;
+    let (imported, skipped) = merge(&mut config.content, incoming, strategy)?;
+
+    let templates_dir = get_templates_dir()?;
+    let mut replaced: Option<TrashEntry> = None;
+    for name in &imported {
+        // A stale checkout would shadow the newly registered source
+        let destination = templates_dir.join(name);
+        if destination.exists() {
+            if replaced.is_none() {
+                replaced = Some(Trash::open()?.create_entry(&format!("import {source}"))?);
+            }
+            if let Some(entry) = replaced.as_mut() {
+                entry.store(&destination)?;
+            }
+        }
+        if clone_now {
+            fetch_template(name, &config.content.templates[name], false).await?;
+        }
+    }
+
+    config.save()?;
+
+    if !quiet {
+        for name in &skipped {
+            println!(
+                "{} Skipped '{}': already registered",
+                style("⚠️").yellow(),
+                name
+            );
+        }
+        println!(
+            "{} Imported {} template(s), skipped {}",
+            style("✨").cyan(),
+            imported.len(),
+            skipped.len()
+        );
+        if !clone_now && !imported.is_empty() {
+            println!("Templates will be cloned on first use with 'ogito new'.");
+        }
+        if let Some(entry) = &replaced {
+            print_undo_hint(entry);
+        }
+    }
+
+    Ok(())
+}
+
+fn is_remote(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+/// Checks the names and sources of the templates in `incoming`. A registry
+/// fetched from `remote` may not point at local directories.
+fn validate(incoming: &Manifest, remote: bool) -> Result<()> {
+    for (name, template) in &incoming.templates {
+        validate_name(name)?;
+        for alias in &template.aliases {
+            validate_alias(alias)?;
+        }
+        if remote && (template.link || template.is_local()) {
+            return Err(eyre!(
+                "Template '{}' points at a local directory, which a remote registry cannot register",
+                name
+            ));
+        }
+        if !is_valid_url(&template.url)? && !template.is_local() {
+            return Err(eyre!(
+                "Invalid URL for template '{}': {}",
+                name,
+                template.url
+            ));
+        }
+    }
+    Ok(())
+}
+
+async fn read_source(source: &str) -> Result<String> {
+    if is_remote(source) {
+        let response = Network::from_settings()?.get(source).await?;
+        if !response.status().is_success() {
+            return Err(eyre!(
+                "Download failed with status {}: {}",
+                response.status(),
+                source
+            ));
+        }
+        Ok(response.text().await?)
+    } else {
+        fs::read_to_string(source).map_err(|e| eyre!("Failed to read '{}': {}", source, e))
+    }
+}
+
+/// Parses a registry exported as either JSON or TOML.
+fn parse(content: &str) -> Result<Manifest> {
+    if content.trim_start().starts_with('{') {
+        let value: serde_json::Value =
+            serde_json::from_str(content).map_err(|e| eyre!("Invalid registry file: {}", e))?;
+        check_schema_version(value.get("schema_version").and_then(|v| v.as_i64()))?;
+        return serde_json::from_value(value).map_err(|e| eyre!("Invalid registry file: {}", e));
+    }
+    Manifest::from_toml(content).map_err(|e| eyre!("Invalid registry file: {}", e))
+}
+
+/// Merges `incoming` into `manifest`, returning the imported and skipped names.
+fn merge(
+    manifest: &mut Manifest,
+    incoming: Manifest,
+    strategy: Strategy,
+) -> Result<(Vec<String>, Vec<String>)> {
+    if strategy == Strategy::Abort {
+        let conflicts: Vec<&String> = incoming
+            .templates
+            .keys()
+            .filter(|name| manifest.templates.contains_key(*name))
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(eyre!(
+                "Templates already registered: {}",
+                conflicts
+                    .iter()
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+    }
+
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    for (name, template) in incoming.templates {
+        if manifest.templates.contains_key(&name) && strategy == Strategy::Skip {
+            skipped.push(name);
+            continue;
+        }
+        manifest.check_conflicts(&name, &template)?;
+        manifest.add_template(name.clone(), template);
+        imported.push(name);
+    }
+
+    Ok((imported, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::Template;
+
+    fn template(url: &str) -> Template {
+        Template {
+            description: None,
+            aliases: Vec::new(),
+            url: url.to_string(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_toml_and_json() -> Result<()> {
+        let toml_content = r#"
+[templates.react]
+url = "https://github.com/owner/react"
+alias = "r"
+ref = "v1"
+"#;
+        let json_content = r#"{"templates":{"react":{"url":"https://github.com/owner/react","alias":"r","ref":"v1"}}}"#;
+
+        let from_toml = parse(toml_content)?;
+        let from_json = parse(json_content)?;
+        assert_eq!(from_toml.templates, from_json.templates);
+        assert_eq!(from_toml.templates["react"].git_ref, Some("v1".to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_rejects_unsafe_templates() {
+        let registry = |name: &str, template: Template| {
+            let mut manifest = Manifest::default();
+            manifest.add_template(name.to_string(), template);
+            manifest
+        };
+
+        assert!(validate(&registry("react", template("https://github.com/o/r")), true).is_ok());
+        assert!(validate(
+            &registry("../..", template("https://github.com/o/r")),
+            false
+        )
+        .is_err());
+        assert!(validate(
+            &registry("/home/me", template("https://github.com/o/r")),
+            false
+        )
+        .is_err());
+
+        let mut aliased = template("https://github.com/o/r");
+        for alias in ["../x", "", "@acme/react", "a/b", "my alias"] {
+            aliased.aliases = vec![alias.to_string()];
+            assert!(validate(&registry("react", aliased.clone()), false).is_err());
+        }
+        aliased.aliases = vec!["r".to_string(), "react-app".to_string()];
+        assert!(validate(&registry("react", aliased), false).is_ok());
+
+        let mut linked = template("/home/me/starter");
+        linked.link = true;
+        assert!(validate(&registry("starter", linked.clone()), false).is_ok());
+        assert!(validate(&registry("starter", linked), true).is_err());
+    }
+
+    #[test]
+    fn test_merge_strategies() -> Result<()> {
+        let mut existing = Manifest::default();
+        existing.add_template("a".to_string(), template("https://github.com/o/a"));
+
+        let mut incoming = Manifest::default();
+        incoming.add_template("a".to_string(), template("https://github.com/o/new-a"));
+        incoming.add_template("b".to_string(), template("https://github.com/o/b"));
+
+        let mut skip = existing.clone();
+        let (imported, skipped) = merge(&mut skip, incoming.clone(), Strategy::Skip)?;
+        assert_eq!(imported.len(), 1);
+        assert_eq!(skipped, vec!["a".to_string()]);
+        assert_eq!(skip.templates["a"].url, "https://github.com/o/a");
+
+        let mut overwrite = existing.clone();
+        merge(&mut overwrite, incoming.clone(), Strategy::Overwrite)?;
+        assert_eq!(overwrite.templates["a"].url, "https://github.com/o/new-a");
+        assert_eq!(overwrite.templates.len(), 2);
+
+        let mut abort = existing.clone();
+        assert!(merge(&mut abort, incoming, Strategy::Abort).is_err());
+        assert_eq!(abort.templates.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+use crate::cmd::clear::dir::compute_dir_stats;
+use crate::file::cache::{get_manifest_path, get_templates_dir};
+use crate::manifest::Manifest;
+use crate::mode::Site;
+use crate::regex::extract_host;
+use crate::registry;
+use clap::ArgMatches;
+use color_eyre::{eyre::eyre, Result};
+use comfy_table::{Cell, ContentArrangement, Table};
+use console::style;
+use git2::Repository;
+use indicatif::{HumanBytes, HumanDuration};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub async fn run(matches: &ArgMatches) -> Result<()> {
+    let name = matches
+        .get_one::<String>("name")
+        .ok_or_else(|| eyre!("Template name is required"))?;
+
+    let config_path = get_manifest_path()?;
+    if !config_path.exists() {
+        return Err(eyre!(
+            "No templates configured. Use 'ogito add' to add one."
+        ));
+    }
+
+    let manifest = registry::merged(&Manifest::read(&config_path)?).await?;
+    let actual_name = manifest
+        .find(name)
+        .ok_or_else(|| eyre!("Template '{}' not found", name))?;
+    let template = &manifest.templates[actual_name];
+    let source = template.source_dir(actual_name, &get_templates_dir()?);
+
+    let none = || "None".to_string();
+    let mut rows = vec![
+        ("Name", actual_name.clone()),
+        (
+            "Aliases",
+            if template.aliases.is_empty() {
+                none()
+            } else {
+                template.aliases.join(", ")
+            },
+        ),
+        (
+            "Description",
+            template.description.clone().unwrap_or_else(none),
+        ),
+        (
+            "Tags",
+            if template.tags.is_empty() {
+                none()
+            } else {
+                template.tags.join(", ")
+            },
+        ),
+        ("Source", template.url.clone()),
+        ("Host", host(&template.url)),
+        ("Ref", template.git_ref.clone().unwrap_or_else(none)),
+    ];
+
+    if source.is_dir() {
+        let (file_count, total_bytes) = compute_dir_stats(&source)?;
+        rows.push(("Commit", commit(&source).unwrap_or_else(none)));
+        rows.push(("Last update", last_update(&source).unwrap_or_else(none)));
+        rows.push((
+            "Size",
+            format!("{} ({} files)", HumanBytes(total_bytes), file_count),
+        ));
+        rows.push(("Path", source.display().to_string()));
+        for (key, value) in project_metadata(&source) {
+            rows.push((key, value));
+        }
+    } else {
+        rows.push(("Path", format!("{} (not fetched yet)", source.display())));
+    }
+
+    let mut table = Table::new();
+    for (key, value) in rows {
+        table.add_row(vec![Cell::new(key), Cell::new(value)]);
+    }
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    println!("{table}");
+
+    if let Some(paragraph) = readme_summary(&source) {
+        println!("\n{}\n{}", style("README").bold(), paragraph);
+    }
+
+    Ok(())
+}
+
+fn host(url: &str) -> String {
+    if Path::new(url).is_absolute() {
+        return "Local directory".to_string();
+    }
+    match extract_host(url).map(Site::from) {
+        Some(Site::Github) => "GitHub".to_string(),
+        Some(Site::Gitlab) => "GitLab".to_string(),
+        _ => "Unknown".to_string(),
+    }
+}
+
+/// Returns the short hash and summary of the commit the cached copy is at.
+fn commit(dir: &Path) -> Option<String> {
+    let repo = Repository::open(dir).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    let hash = commit.id().to_string();
+    let summary = commit.summary().unwrap_or_default().to_string();
+    Some(format!("{} {}", &hash[..7], summary))
+}
+
+/// Returns how long ago the commit the cached copy is at was made.
+fn last_update(dir: &Path) -> Option<String> {
+    let repo = Repository::open(dir).ok()?;
+    let seconds = repo.head().ok()?.peel_to_commit().ok()?.time().seconds();
+    let committed = UNIX_EPOCH + Duration::from_secs(u64::try_from(seconds).ok()?);
+    let elapsed = SystemTime::now().duration_since(committed).ok()?;
+    Some(format!("{} ago", HumanDuration(elapsed)))
+}
+
+/// Reads the name, version and description declared by the template's own
+/// `package.json`, `Cargo.toml` or `pyproject.toml`.
+fn project_metadata(dir: &Path) -> Vec<(&'static str, String)> {
+    let fields = |get: &dyn Fn(&str) -> Option<String>| {
+        [
+            ("Project name", "name"),
+            ("Project version", "version"),
+            ("Project description", "description"),
+        ]
+        .into_iter()
+        .filter_map(|(label, key)| get(key).map(|value| (label, value)))
+        .collect::<Vec<_>>()
+    };
+
+    if let Ok(content) = fs::read_to_string(dir.join("package.json")) {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+            return fields(&|key| json.get(key)?.as_str().map(String::from));
+        }
+    }
+
+    for (file, section) in [("Cargo.toml", "package"), ("pyproject.toml", "project")] {
+        let Ok(content) = fs::read_to_string(dir.join(file)) else {
+            continue;
+        };
+        if let Ok(table) = content.parse::<toml::Table>() {
+            if let Some(section) = table.get(section) {
+                return fields(&|key| section.get(key)?.as_str().map(String::from));
+            }
+        }
+    }
+
+    Vec::new()
+}
+
+fn readme_summary(dir: &Path) -> Option<String> {
+    let readme = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.is_file()
+                && path
+                    .file_stem()
+                    .is_some_and(|stem| stem.eq_ignore_ascii_case("readme"))
+        })?;
+    first_paragraph(&fs::read_to_string(readme).ok()?)
+}
+
+/// Returns the first paragraph of prose in a README, skipping headings,
+/// badges and HTML.
+fn first_paragraph(content: &str) -> Option<String> {
+    let is_decoration = |line: &str| {
+        line.starts_with('#')
+            || line.starts_with("[![")
+            || line.starts_with("![")
+            || line.starts_with('<')
+            || line.starts_with("---")
+            || line.starts_with("===")
+    };
+
+    let mut paragraph: Vec<&str> = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || is_decoration(line) {
+            if !paragraph.is_empty() {
+                break;
+            }
+            continue;
+        }
+        paragraph.push(line);
+    }
+    (!paragraph.is_empty()).then(|| paragraph.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_paragraph() {
+        let readme = "# My Template\n\n[![CI](badge.svg)](ci)\n<p align=\"center\">logo</p>\n\nA starter for\nRust CLIs.\n\n## Usage\nRun it.\n";
+        assert_eq!(
+            first_paragraph(readme),
+            Some("A starter for Rust CLIs.".to_string())
+        );
+        assert_eq!(first_paragraph("# Title only\n"), None);
+    }
+
+    #[test]
+    fn test_project_metadata() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"starter\"\nversion = \"0.1.0\"\n",
+        )?;
+        assert_eq!(
+            project_metadata(dir.path()),
+            vec![
+                ("Project name", "starter".to_string()),
+                ("Project version", "0.1.0".to_string())
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_last_update_uses_head_commit() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert_eq!(last_update(dir.path()), None);
+
+        let repo = Repository::init(dir.path())?;
+        let two_days_ago = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() - 2 * 86_400;
+        let time = git2::Time::new(two_days_ago as i64, 0);
+        let signature = git2::Signature::new("ogito", "ogito@example.com", &time)?;
+        let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])?;
+
+        assert_eq!(last_update(dir.path()), Some("2 days ago".to_string()));
+        Ok(())
+    }
+}
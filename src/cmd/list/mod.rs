@@ -1,13 +1,13 @@
-use crate::file::cache::get_cache_root;
-use crate::manifest::ManifestFile;
+use crate::file::cache::get_manifest_path;
+use crate::manifest::{normalize_tags, Manifest};
+use crate::registry;
 use clap::ArgMatches;
 use color_eyre::{eyre::eyre, Result};
 use comfy_table::{Cell, ContentArrangement, Table};
 
-pub fn run(_matches: &ArgMatches) -> Result<()> {
+pub async fn run(matches: &ArgMatches) -> Result<()> {
     //TODO fuzzy search
-    let cache_path = get_cache_root();
-    let config_path = cache_path.join("template.toml");
+    let config_path = get_manifest_path()?;
 
     if !config_path.exists() {
         return Err(eyre!(
@@ -15,30 +15,41 @@ pub fn run(_matches: &ArgMatches) -> Result<()> {
         ));
     }
 
-    let config = ManifestFile::load(&config_path)?;
-    let templates = &config.content.templates;
+    let tags = normalize_tags(matches.get_many::<String>("tag").unwrap_or_default())?;
+    let manifest = registry::merged(&Manifest::read(&config_path)?).await?;
+    let templates: Vec<_> = manifest
+        .templates
+        .iter()
+        .filter(|(_, template)| template.has_tags(&tags))
+        .collect();
 
     if templates.is_empty() {
-        println!("No templates found.");
+        if tags.is_empty() {
+            println!("No templates found.");
+        } else {
+            println!("No templates tagged {}.", tags.join(", "));
+        }
         return Ok(());
     }
 
     let mut table = Table::new();
-    table.set_header(vec!["Name", "Description", "Alias"]);
+    table.set_header(vec!["Name", "Description", "Alias", "Tags"]);
 
     for (name, template) in templates {
         let description = match &template.description {
             Some(des) => des,
             None => &"None".to_string(),
         };
-        let alias = match &template.alias {
-            Some(alias) => alias,
-            None => &"None".to_string(),
+        let alias = if template.aliases.is_empty() {
+            "None".to_string()
+        } else {
+            template.aliases.join(", ")
         };
         table.add_row(vec![
             Cell::new(name),
             Cell::new(description),
             Cell::new(alias),
+            Cell::new(template.tags.join(", ")),
         ]);
     }
     table.set_content_arrangement(ContentArrangement::Dynamic);
@@ -1,6 +1,17 @@
 pub mod add;
 pub mod clear;
+pub mod config;
+pub mod doctor;
+pub mod edit;
+pub mod export;
+pub mod import;
+pub mod info;
 pub mod list;
 pub mod new;
+pub mod registry;
 pub mod remove;
+pub mod rename;
+pub mod save_as;
+pub mod trash;
+pub mod undo;
 pub mod update;
@@ -1,64 +1,112 @@
 use crate::{
-    clone::{clone, force_clone},
+    clone::clone,
+    cmd::new::{
+        merge::{merge_project, merge_strategy, staging_dir},
+        preview::{dir_name, print_preview},
+        setup_repository,
+    },
     fetch::config::Config,
-    file::{json::update_package_json_in_dir, path::sanitize_dir},
-    regex::extract_path,
+    file::{
+        exclude::ExcludeOptions, path::sanitize_dir, project::rename_project, staging::Staging,
+    },
+    trash::print_undo_hint,
+    user_config::UserConfig,
 };
 use clap::ArgMatches;
 use color_eyre::{eyre::eyre, Result};
 use console::{style, Emoji};
 use dialoguer::Confirm;
 use indicatif::HumanDuration;
-use std::{fs, time::Instant};
+use std::{fs, path::Path, time::Instant};
 
 static FINISH: Emoji<'_, '_> = Emoji("🚀", "🚀");
 static FIRE: Emoji<'_, '_> = Emoji("🔥", "🔥");
 
-pub async fn direct_clone(matches: &ArgMatches, url: &String) -> Result<()> {
-    let mode = matches.get_one::<String>("mode").unwrap();
-    let branch = matches.get_one::<String>("branch");
+pub async fn direct_clone(
+    matches: &ArgMatches,
+    url: &String,
+    dir_str: &str,
+    exclude: ExcludeOptions,
+) -> Result<()> {
+    let settings = UserConfig::load()?;
+    let mode = matches
+        .get_one::<String>("mode")
+        .cloned()
+        .or_else(|| settings.resolve("mode"))
+        .unwrap_or_else(|| "git".to_string());
+    let default_branch = settings.resolve("branch");
+    let branch = matches
+        .get_one::<String>("branch")
+        .or(default_branch.as_ref());
     let force = matches.get_flag("force");
-    let keep_history = matches.get_flag("keep-history");
-
-    let (_, repo_dir) = extract_path(url).ok_or_else(|| eyre!("Invalid URL"))?;
-    let dir_str = match matches.get_one::<String>("dir") {
-        Some(dir) => dir,
-        None => &repo_dir.to_string(),
-    };
+    let keep_history = !matches.get_flag("no-keep-history")
+        && (matches.get_flag("keep-history")
+            || settings.resolve_bool("keep_history") == Some(true));
 
     let dir_path = sanitize_dir(dir_str)?;
-    let dir_string = dir_path
-        .to_str()
-        .ok_or_else(|| eyre!("Invalid directory name: contains non-UTF-8 characters"))?
-        .to_string();
 
-    let config = Config::from(&dir_string, mode.into(), force, keep_history, branch);
-    let started = Instant::now();
+    if matches.get_flag("dry-run") {
+        // Clone into a scratch directory so the preview reflects the real files
+        let (_staging, staged) = staging_dir(&dir_path)?;
+        let staged_string = utf8(&staged)?;
+        let config = Config::from(&staged_string, (&mode).into(), false, keep_history, branch)
+            .with_exclude(exclude);
+        clone(&url.to_string(), &config).await?;
+        return print_preview(
+            &staged,
+            &dir_path,
+            dir_name(&dir_path)?,
+            matches.get_flag("reset-metadata"),
+            merge_strategy(matches, &dir_path)?,
+            false,
+        );
+    }
 
-    if dir_path.exists() {
-        let mut empty = fs::read_dir(&dir_path)?;
-        if empty.next().is_some() {
-            let force = config.force
+    let started = Instant::now();
+    if let Some(strategy) = merge_strategy(matches, &dir_path)? {
+        if keep_history {
+            println!(
+                "{} --keep-history is ignored when merging into an existing directory",
+                style("ℹ️").cyan()
+            );
+        }
+        let staging = Staging::new(&dir_path)?;
+        let staged_string = utf8(staging.path())?;
+        let config = Config::from(&staged_string, (&mode).into(), false, false, branch)
+            .with_exclude(exclude);
+        clone(&url.to_string(), &config).await?;
+        merge_project(matches, staging.path(), &dir_path, strategy, false)?;
+        setup_repository(matches, &dir_path, None)?;
+    } else {
+        if dir_path.exists() && fs::read_dir(&dir_path)?.next().is_some() {
+            let force = force
                 || Confirm::new()
                     .with_prompt("Do you want to overwrite existing files?")
                     .default(false)
                     .interact()
                     .map_err(|e| eyre!("Failed to interact with user: {}", e))?;
-            if force {
-                force_clone(&url.to_string(), &dir_string, &config).await?;
-            } else {
+            if !force {
                 println!("{}", style("❌ Directory is not empty").red().bold());
                 return Err(eyre!("Directory is not empty"));
             }
-        } else {
-            clone(&url.to_string(), &config).await?;
         }
-    } else {
+
+        // Generated beside the destination and moved there once complete
+        let staging = Staging::new(&dir_path)?;
+        let staged = staging.path();
+        let staged_string = utf8(staged)?;
+        let config = Config::from(&staged_string, (&mode).into(), force, keep_history, branch)
+            .with_exclude(exclude);
         clone(&url.to_string(), &config).await?;
-    }
 
-    if let Some(dir_name) = dir_path.file_name().and_then(|n| n.to_str()) {
-        update_package_json_in_dir(&dir_path, dir_name)?;
+        let project_name = dir_name(&dir_path)?;
+        rename_project(staged, project_name, matches.get_flag("reset-metadata"))?;
+        // A kept history still points at the template repository
+        setup_repository(matches, staged, keep_history.then_some(url.as_str()))?;
+
+        if let Some(entry) = staging.commit(&format!("new {}", dir_path.display()))? {
+            print_undo_hint(&entry);
+        }
     }
 
     println!("{} Done in {}", FINISH, HumanDuration(started.elapsed()));
@@ -71,3 +119,10 @@ pub async fn direct_clone(matches: &ArgMatches, url: &String) -> Result<()> {
     );
     Ok(())
 }
+
+/// Returns `path` as the string clone configurations expect.
+fn utf8(path: &Path) -> Result<String> {
+    path.to_str()
+        .map(String::from)
+        .ok_or_else(|| eyre!("Invalid directory name: contains non-UTF-8 characters"))
+}
@@ -0,0 +1,202 @@
+use crate::git::init_repository;
+use clap::ArgMatches;
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use git2::Repository;
+use std::{fs, path::Path};
+
+/// Name the template's remote is renamed to in a project keeping its history.
+pub const TEMPLATE_REMOTE: &str = "template";
+
+/// How a project generated with `--keep-history` treats the template repository.
+pub struct HistoryOptions {
+    /// Keep the template as the `template` remote rather than removing it.
+    pub keep_template_remote: bool,
+    /// Replace the template's history with a single commit.
+    pub squash: bool,
+}
+
+impl HistoryOptions {
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        Self {
+            keep_template_remote: matches
+                .get_one::<String>("template-remote")
+                .is_none_or(|action| action == "rename"),
+            squash: matches.get_flag("squash"),
+        }
+    }
+}
+
+/// Detaches a project cloned with its history from the template repository at
+/// `url`, so pushing the project never reaches the template.
+pub fn detach_template(
+    dir: &Path,
+    url: &str,
+    options: &HistoryOptions,
+    author: Option<(&str, &str)>,
+) -> Result<()> {
+    if !dir.join(".git").exists() {
+        return Ok(());
+    }
+
+    let repo = Repository::open(dir)?;
+    if options.squash {
+        let head = repo.head()?;
+        let branch = head
+            .shorthand()
+            .ok_or_else(|| eyre!("The template's HEAD is not a branch"))?
+            .to_string();
+        let commit = head.peel_to_commit()?.id().to_string();
+        drop(head);
+        drop(repo);
+
+        fs::remove_dir_all(dir.join(".git"))?;
+        let message = format!("Initial commit from {}@{}", url, &commit[..7]);
+        init_repository(dir, &branch, &message, author)?;
+        println!(
+            "{} Squashed the template history into \"{}\"",
+            style("📦").cyan(),
+            message
+        );
+
+        if options.keep_template_remote {
+            Repository::open(dir)?.remote(TEMPLATE_REMOTE, url)?;
+            println!(
+                "{} Added the template as the '{}' remote",
+                style("🔗").cyan(),
+                TEMPLATE_REMOTE
+            );
+        }
+        return Ok(());
+    }
+
+    if repo.find_remote("origin").is_err() {
+        return Ok(());
+    }
+    if options.keep_template_remote {
+        repo.remote_rename("origin", TEMPLATE_REMOTE)?;
+        println!(
+            "{} Renamed the template remote 'origin' to '{}'",
+            style("🔗").cyan(),
+            TEMPLATE_REMOTE
+        );
+    } else {
+        repo.remote_delete("origin")?;
+        println!(
+            "{} Removed the template remote 'origin'",
+            style("🔗").cyan()
+        );
+    }
+    Ok(())
+}
+
+/// Adds `url` as the `origin` remote of the project's repository.
+pub fn add_remote(dir: &Path, url: &str) -> Result<()> {
+    let repo = Repository::open(dir).map_err(|_| {
+        eyre!("--remote needs a git repository: pass --keep-history or --git-init as well")
+    })?;
+    if repo.find_remote("origin").is_ok() {
+        return Err(eyre!("The project already has an 'origin' remote"));
+    }
+    repo.remote("origin", url)?;
+    println!(
+        "{} Added {} as the 'origin' remote",
+        style("🔗").cyan(),
+        style(url).bold()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use tempfile::tempdir;
+
+    /// Creates a template repository with two commits and an `origin` remote.
+    fn template_repo(dir: &Path) -> Result<String> {
+        let repo = Repository::init(dir)?;
+        let signature = Signature::now("Template", "template@example.com")?;
+        let mut head = None;
+        for (file, content) in [("README.md", "# starter\n"), ("main.rs", "fn main() {}\n")] {
+            fs::write(dir.join(file), content)?;
+            let mut index = repo.index()?;
+            index.add_path(Path::new(file))?;
+            let tree = repo.find_tree(index.write_tree()?)?;
+            let parents = match head {
+                Some(oid) => vec![repo.find_commit(oid)?],
+                None => Vec::new(),
+            };
+            let parents: Vec<_> = parents.iter().collect();
+            head =
+                Some(repo.commit(Some("HEAD"), &signature, &signature, file, &tree, &parents)?);
+        }
+        repo.remote("origin", "https://github.com/acme/starter")?;
+        Ok(head.unwrap().to_string())
+    }
+
+    #[test]
+    fn test_detach_template_renames_origin() -> Result<()> {
+        let dir = tempdir()?;
+        template_repo(dir.path())?;
+        let options = HistoryOptions {
+            keep_template_remote: true,
+            squash: false,
+        };
+        detach_template(
+            dir.path(),
+            "https://github.com/acme/starter",
+            &options,
+            None,
+        )?;
+
+        let repo = Repository::open(dir.path())?;
+        assert!(repo.find_remote("origin").is_err());
+        assert_eq!(
+            repo.find_remote(TEMPLATE_REMOTE)?.url(),
+            Some("https://github.com/acme/starter")
+        );
+        assert_eq!(repo.head()?.peel_to_commit()?.parent_count(), 1);
+
+        add_remote(dir.path(), "https://github.com/me/shop")?;
+        assert_eq!(
+            repo.find_remote("origin")?.url(),
+            Some("https://github.com/me/shop")
+        );
+        assert!(add_remote(dir.path(), "https://github.com/me/other").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_detach_template_squashes() -> Result<()> {
+        let dir = tempdir()?;
+        let head = template_repo(dir.path())?;
+        let options = HistoryOptions {
+            keep_template_remote: false,
+            squash: true,
+        };
+        detach_template(
+            dir.path(),
+            "https://github.com/acme/starter",
+            &options,
+            Some(("Jane Doe", "jane@example.com")),
+        )?;
+
+        let repo = Repository::open(dir.path())?;
+        assert!(repo.remotes()?.is_empty());
+        let commit = repo.head()?.peel_to_commit()?;
+        assert_eq!(commit.parent_count(), 0);
+        assert_eq!(
+            commit.message(),
+            Some(
+                format!(
+                    "Initial commit from https://github.com/acme/starter@{}",
+                    &head[..7]
+                )
+                .as_str()
+            )
+        );
+        assert!(commit.tree()?.get_path(Path::new("main.rs")).is_ok());
+        Ok(())
+    }
+}
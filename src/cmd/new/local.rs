@@ -1,27 +1,46 @@
+use crate::cmd::add::fetch_template;
+use crate::cmd::new::merge::{merge_project, merge_strategy, staging_dir};
+use crate::cmd::new::preview::{dir_name, print_preview};
+use crate::cmd::new::setup_repository;
 use crate::file::{
-    cache::get_cache_root, copy::create_template, json::update_package_json_in_dir,
+    cache::{get_manifest_path, get_templates_dir},
+    copy::create_template,
+    exclude::{ExcludeOptions, ExcludeRules},
     path::sanitize_dir,
+    placeholder::render_placeholders,
+    project::rename_project,
+    staging::Staging,
 };
 use crate::manifest::Manifest;
 use crate::progress::create_spinner;
+use crate::registry;
+use crate::trash;
 use clap::ArgMatches;
 use color_eyre::eyre::Ok;
 use color_eyre::{eyre::eyre, Result};
 use console::{style, Emoji};
 use dialoguer::Confirm;
-use indicatif::HumanDuration;
-use std::{fs, time::Instant};
+use indicatif::{HumanDuration, ProgressBar};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 static FINISH: Emoji<'_, '_> = Emoji("🚀", "🚀");
 static FIRE: Emoji<'_, '_> = Emoji("🔥", "🔥");
 
-pub async fn local_template(matches: &ArgMatches, template_name: &String) -> Result<()> {
+pub async fn local_template(
+    matches: &ArgMatches,
+    template_name: &String,
+    dir_str: &str,
+    exclude: ExcludeOptions,
+) -> Result<()> {
     let started = Instant::now();
     let pb = create_spinner("🔍 Looking for template...");
 
-    let cache_path = get_cache_root();
-    let config_path = cache_path.join("template.toml");
-    let template_path = cache_path.join("templates");
+    let config_path = get_manifest_path()?;
+    let template_path = get_templates_dir()?;
 
     if !config_path.exists() {
         pb.finish_and_clear();
@@ -30,48 +49,66 @@ pub async fn local_template(matches: &ArgMatches, template_name: &String) -> Res
         ));
     }
 
-    let toml_content = fs::read_to_string(&config_path)?;
-    let toml_config: Manifest = toml::from_str(&toml_content)?;
+    let toml_config = registry::merged(&Manifest::read(&config_path)?).await?;
     let path_name = toml_config
         .find(template_name)
         .ok_or_else(|| eyre!("Template '{}' not found", template_name))?;
-    let source = template_path.join(path_name);
+    let template = &toml_config.templates[path_name];
+    let source = template.source_dir(path_name, &template_path);
+
+    if template.link && !source.is_dir() {
+        pb.finish_and_clear();
+        return Err(eyre!(
+            "Linked template '{}' points to a missing directory: {}",
+            path_name,
+            source.display()
+        ));
+    }
+
+    // Imported templates are registered before they are cloned
+    if !source.exists() {
+        pb.set_message("📥 Fetching template...");
+        fetch_template(path_name, template, false).await?;
+    }
 
     pb.set_message("📁 Preparing destination directory...");
-    let dir_str = match matches.get_one::<String>("dir") {
-        Some(dir) => dir,
-        None => &template_name.to_string(),
-    };
     let dest_path = sanitize_dir(dir_str)?;
 
-    // Check if destination directory exists and is not empty
-    let force = matches.get_flag("force");
-    if dest_path.exists() {
-        let mut empty = fs::read_dir(&dest_path)?;
-        if empty.next().is_some() {
-            if !force {
-                pb.finish_and_clear();
-                let confirm = Confirm::new()
-                    .with_prompt("Do you want to overwrite existing files?")
-                    .default(false)
-                    .interact()
-                    .map_err(|e| eyre!("Failed to interact with user: {}", e))?;
-                if !confirm {
-                    return Err(eyre!("❌ Directory is not empty"));
-                }
-            }
-            // Remove existing directory content
-            pb.set_message("🗑️  Removing existing files...");
-            fs::remove_dir_all(&dest_path)?;
-        }
+    let rules = ExcludeRules::load(&source, &exclude)?;
+    // Only snapshots saved with a placeholder are rendered
+    let placeholders = template.project_name.is_some();
+    if matches.get_flag("dry-run") {
+        pb.finish_and_clear();
+        let (_staging, staged) = staging_dir(&dest_path)?;
+        let project_name = dir_name(&dest_path)?;
+        create_template(source, staged.clone(), &rules)?;
+        return print_preview(
+            &staged,
+            &dest_path,
+            project_name,
+            matches.get_flag("reset-metadata"),
+            merge_strategy(matches, &dest_path)?,
+            placeholders,
+        );
     }
 
-    pb.set_message("📋 Copying template files...");
-    create_template(source, dest_path.clone())?;
+    if let Some(strategy) = merge_strategy(matches, &dest_path)? {
+        pb.set_message("📋 Copying template files...");
+        let staging = Staging::new(&dest_path)?;
+        create_template(source, staging.path().to_path_buf(), &rules)?;
+        pb.finish_and_clear();
+        merge_project(matches, staging.path(), &dest_path, strategy, placeholders)?;
+        setup_repository(matches, &dest_path, None)?;
+    } else {
+        confirm_overwrite(matches, &pb, &dest_path)?;
 
-    // Update package.json name if it exists
-    if let Some(dir_name) = dest_path.file_name().and_then(|n| n.to_str()) {
-        update_package_json_in_dir(&dest_path, dir_name)?;
+        // Generated beside the destination and moved there once complete
+        let staging = Staging::new(&dest_path)?;
+        copy_template(matches, &pb, source, staging.path(), &rules, placeholders)?;
+        pb.suspend(|| setup_repository(matches, staging.path(), None))?;
+        if let Some(entry) = staging.commit(&format!("new {}", dest_path.display()))? {
+            pb.suspend(|| trash::print_undo_hint(&entry));
+        }
     }
 
     pb.finish_and_clear();
@@ -86,3 +123,47 @@ pub async fn local_template(matches: &ArgMatches, template_name: &String) -> Res
     );
     Ok(())
 }
+
+/// Asks before replacing the content of a non-empty `dest_path`, unless
+/// `--force` is passed.
+fn confirm_overwrite(matches: &ArgMatches, pb: &ProgressBar, dest_path: &Path) -> Result<()> {
+    if matches.get_flag("force") || !dest_path.exists() {
+        return Ok(());
+    }
+    if fs::read_dir(dest_path)?.next().is_none() {
+        return Ok(());
+    }
+    pb.finish_and_clear();
+    let confirm = Confirm::new()
+        .with_prompt("Do you want to overwrite existing files?")
+        .default(false)
+        .interact()
+        .map_err(|e| eyre!("Failed to interact with user: {}", e))?;
+    if !confirm {
+        return Err(eyre!("❌ Directory is not empty"));
+    }
+    Ok(())
+}
+
+/// Copies the template into `staged` and names the project after it,
+/// rendering its placeholders when it declares them.
+fn copy_template(
+    matches: &ArgMatches,
+    pb: &ProgressBar,
+    source: PathBuf,
+    staged: &Path,
+    rules: &ExcludeRules,
+    placeholders: bool,
+) -> Result<()> {
+    pb.set_message("📋 Copying template files...");
+    create_template(source, staged.to_path_buf(), rules)?;
+
+    // Update package.json name if it exists
+    if let Some(dir_name) = staged.file_name().and_then(|n| n.to_str()) {
+        if placeholders {
+            render_placeholders(staged, dir_name)?;
+        }
+        rename_project(staged, dir_name, matches.get_flag("reset-metadata"))?;
+    }
+    Ok(())
+}
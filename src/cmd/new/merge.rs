@@ -0,0 +1,170 @@
+use crate::cmd::new::preview::dir_name;
+use crate::file::{
+    merge::{apply_merge, plan_merge, Outcome, Strategy},
+    placeholder::render_placeholders,
+    project::rename_project,
+    staging::uninterruptible,
+};
+use crate::trash::{print_undo_hint, Trash};
+use clap::ArgMatches;
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use dialoguer::{theme::ColorfulTheme, Select};
+use similar::TextDiff;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Returns the strategy to merge into `destination` with, when `--merge` is
+/// passed and `destination` already holds files.
+pub fn merge_strategy(matches: &ArgMatches, destination: &Path) -> Result<Option<Strategy>> {
+    let Some(name) = matches.get_one::<String>("merge") else {
+        return Ok(None);
+    };
+    if !destination.is_dir() || fs::read_dir(destination)?.next().is_none() {
+        return Ok(None);
+    }
+    Strategy::parse(name).map(Some)
+}
+
+/// Creates a scratch directory to generate the project for `destination` in,
+/// returning it with the path of the project inside it.
+pub fn staging_dir(destination: &Path) -> Result<(TempDir, PathBuf)> {
+    let staging = tempfile::tempdir()?;
+    let staged = staging.path().join(dir_name(destination)?);
+    Ok((staging, staged))
+}
+
+/// Finishes the project generated in `staged` and merges it into the existing
+/// `destination`, then reports what happened to each file. Placeholders are
+/// rendered when the template declares them. Every conflict is resolved
+/// before anything is written, and a failed merge is undone.
+pub fn merge_project(
+    matches: &ArgMatches,
+    staged: &Path,
+    destination: &Path,
+    strategy: Strategy,
+    placeholders: bool,
+) -> Result<()> {
+    let project_name = dir_name(destination)?;
+    if placeholders {
+        render_placeholders(staged, project_name)?;
+    }
+    rename_project(staged, project_name, matches.get_flag("reset-metadata"))?;
+
+    let plan = plan_merge(staged, destination, strategy, &mut prompt_conflict)?;
+    let trash = Trash::open()?;
+    let operation = format!("new --merge {}", destination.display());
+    let replaced = uninterruptible(|| apply_merge(staged, destination, &plan, &trash, &operation))?;
+    print_summary(destination, &plan.outcomes);
+    if let Some(entry) = &replaced {
+        print_undo_hint(entry);
+    }
+    Ok(())
+}
+
+/// Shows how `existing` differs from the generated `new` file and asks what to
+/// do with it.
+fn prompt_conflict(relative: &Path, new: &Path, existing: &Path) -> Result<Strategy> {
+    if !console::user_attended() {
+        return Err(eyre!(
+            "Cannot ask about {} without a terminal. Pass --merge=skip, --merge=overwrite or --merge=keep-both.",
+            relative.display()
+        ));
+    }
+
+    println!(
+        "\n{} {} already exists",
+        style("⚠️").yellow(),
+        style(relative.display()).bold()
+    );
+    match (fs::read_to_string(existing), fs::read_to_string(new)) {
+        (Ok(old), Ok(new)) => {
+            let existing_label = format!("{} (existing)", relative.display());
+            let new_label = format!("{} (template)", relative.display());
+            let diff = TextDiff::from_lines(&old, &new)
+                .unified_diff()
+                .header(&existing_label, &new_label)
+                .to_string();
+            for line in diff.lines() {
+                match line.chars().next() {
+                    Some('+') => println!("{}", style(line).green()),
+                    Some('-') => println!("{}", style(line).red()),
+                    Some('@') => println!("{}", style(line).cyan()),
+                    _ => println!("{line}"),
+                }
+            }
+        }
+        _ => println!("Binary files differ"),
+    }
+
+    let choice = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("What should happen to this file?")
+        .items(&[
+            "Keep the existing file",
+            "Overwrite it with the template's version",
+            "Keep both (write the template's version next to it)",
+        ])
+        .default(0)
+        .interact()
+        .map_err(|e| eyre!("Failed to interact with user: {}", e))?;
+    Ok(match choice {
+        1 => Strategy::Overwrite,
+        2 => Strategy::KeepBoth,
+        _ => Strategy::Skip,
+    })
+}
+
+fn print_summary(destination: &Path, outcomes: &[(PathBuf, Outcome)]) {
+    println!(
+        "\n{} Merged into {}:",
+        style("🔀").cyan(),
+        style(destination.display()).bold()
+    );
+    let mut counts = [0; 5];
+    for (path, outcome) in outcomes {
+        let path = path.display();
+        match outcome {
+            Outcome::Created => {
+                counts[0] += 1;
+                println!("  {} {}", style("+").green(), path);
+            }
+            Outcome::Unchanged => {
+                counts[1] += 1;
+                println!(
+                    "  {} {} {}",
+                    style("=").dim(),
+                    path,
+                    style("(unchanged)").dim()
+                );
+            }
+            Outcome::Overwritten => {
+                counts[2] += 1;
+                println!(
+                    "  {} {} {}",
+                    style("~").yellow(),
+                    path,
+                    style("(overwritten, previous version in the trash)").yellow()
+                );
+            }
+            Outcome::Skipped => {
+                counts[3] += 1;
+                println!(
+                    "  {} {} {}",
+                    style("-").dim(),
+                    path,
+                    style("(kept existing)").dim()
+                );
+            }
+            Outcome::KeptBoth(kept) => {
+                counts[4] += 1;
+                let kept = kept.strip_prefix(destination).unwrap_or(kept);
+                println!("  {} {} -> {}", style("±").cyan(), path, kept.display());
+            }
+        }
+    }
+    println!(
+        "{} created, {} unchanged, {} overwritten, {} kept existing, {} kept both",
+        counts[0], counts[1], counts[2], counts[3], counts[4]
+    );
+}
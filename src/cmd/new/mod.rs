@@ -1,22 +1,205 @@
 mod clone;
+mod history;
 mod local;
+mod merge;
+mod preview;
 
-use crate::regex::is_valid_url;
+use crate::{
+    file::{cache::get_manifest_path, exclude::ExcludeOptions, path::sanitize_dir},
+    git::init_repository,
+    manifest::Manifest,
+    regex::{extract_path, is_valid_url},
+    user_config::UserConfig,
+};
 use clap::ArgMatches;
 use clone::direct_clone;
 use color_eyre::{eyre::eyre, Result};
+use console::style;
+use dialoguer::{theme::ColorfulTheme, Input};
+use history::{add_remote, detach_template, HistoryOptions};
 use local::local_template;
+use std::path::Path;
+
+const DEFAULT_BRANCH: &str = "main";
+const DEFAULT_COMMIT_MESSAGE: &str = "Initial commit";
 
 pub async fn run(matches: &ArgMatches) -> Result<()> {
     let source = matches
         .get_one::<String>("source")
         .ok_or_else(|| eyre!("Source is required"))?;
 
+    let settings = UserConfig::load()?;
+    let expanded = expand_shorthand(source, &settings)?;
+    let source = expanded.as_ref().unwrap_or(source);
+
     if is_valid_url(source)? {
-        direct_clone(matches, source).await?;
+        let (_, repo) = extract_path(source).ok_or_else(|| eyre!("Invalid URL"))?;
+        let name = resolve_project_name(matches, repo)?;
+        direct_clone(matches, source, &name, exclude_options(matches)).await?;
     } else {
-        local_template(matches, source).await?;
+        let name = resolve_project_name(matches, source)?;
+        local_template(matches, source, &name, exclude_options(matches)).await?;
+    }
+
+    Ok(())
+}
+
+/// Expands an `owner/repo` shorthand that is not a registered template into a
+/// URL on the first preferred host from the user configuration.
+fn expand_shorthand(source: &str, settings: &UserConfig) -> Result<Option<String>> {
+    let Some(host) = settings
+        .resolve("hosts")
+        .and_then(|hosts| hosts.split(',').next().map(|h| h.trim().to_string()))
+    else {
+        return Ok(None);
+    };
+
+    let mut parts = source.split('/');
+    let is_shorthand = matches!(
+        (parts.next(), parts.next(), parts.next()),
+        (Some(owner), Some(repo), None) if !owner.is_empty() && !repo.is_empty() && !owner.starts_with('@')
+    );
+    if !is_shorthand {
+        return Ok(None);
+    }
+
+    let manifest_path = get_manifest_path()?;
+    if manifest_path.exists() {
+        let manifest = Manifest::read(&manifest_path)?;
+        if manifest.find(&source.to_string()).is_some() {
+            return Ok(None);
+        }
+    }
+
+    let url = format!("{}/{source}", host_url(&host));
+    if !is_valid_url(&url)? {
+        return Err(eyre!(
+            "Cannot expand '{}' on '{}': only GitHub and GitLab repositories can be cloned",
+            source,
+            host
+        ));
+    }
+    Ok(Some(url))
+}
+
+/// Returns the base URL of a preferred host: a known short name such as
+/// `gitlab`, or a hostname or URL used as is.
+fn host_url(host: &str) -> String {
+    let host = match host {
+        "github" => "github.com",
+        "gitlab" => "gitlab.com",
+        "bitbucket" => "bitbucket.org",
+        host => host,
+    };
+    if host.contains("://") {
+        host.trim_end_matches('/').to_string()
+    } else {
+        format!("https://{}", host.trim_end_matches('/'))
+    }
+}
+
+/// Sets up version control for the generated project. A template history
+/// kept from `template` is detached from it, a repository is initialized when
+/// `--git-init` or the `git.init` setting asks for one, and `--remote` is
+/// added as `origin`.
+fn setup_repository(matches: &ArgMatches, dir: &Path, template: Option<&str>) -> Result<()> {
+    let settings = UserConfig::load()?;
+    let (name, email) = (
+        settings.resolve("author.name"),
+        settings.resolve("author.email"),
+    );
+    let author = name.as_deref().zip(email.as_deref());
+
+    if let Some(url) = template {
+        detach_template(dir, url, &HistoryOptions::from_matches(matches), author)?;
+    }
+
+    let enabled = !matches.get_flag("no-git-init")
+        && (matches.get_flag("git-init") || settings.resolve_bool("git.init") == Some(true));
+    if enabled && dir.join(".git").exists() {
+        println!(
+            "{} The project already has a git repository, skipping git init",
+            style("ℹ️").cyan()
+        );
+    } else if enabled {
+        let branch = settings
+            .resolve("git.default_branch")
+            .unwrap_or_else(|| DEFAULT_BRANCH.to_string());
+        let message = settings
+            .resolve("git.commit_message")
+            .unwrap_or_else(|| DEFAULT_COMMIT_MESSAGE.to_string());
+        let oid = init_repository(dir, &branch, &message, author)?;
+        println!(
+            "{} Initialized a git repository on {} ({})",
+            style("🌱").green(),
+            style(&branch).bold(),
+            &oid.to_string()[..7]
+        );
     }
 
+    if let Some(remote) = matches.get_one::<String>("remote") {
+        add_remote(dir, remote)?;
+    }
     Ok(())
 }
+
+/// Collects the `--exclude` and `--with` flags.
+fn exclude_options(matches: &ArgMatches) -> ExcludeOptions {
+    let values = |id: &str| {
+        matches
+            .get_many::<String>(id)
+            .unwrap_or_default()
+            .cloned()
+            .collect()
+    };
+    ExcludeOptions {
+        patterns: values("exclude"),
+        features: values("with"),
+    }
+}
+
+/// Resolves the project name from the positional argument, the deprecated
+/// `--dir` flag, or an interactive prompt when running on a terminal.
+fn resolve_project_name(matches: &ArgMatches, default: &str) -> Result<String> {
+    if let Some(name) = matches.get_one::<String>("name") {
+        return Ok(name.clone());
+    }
+
+    if let Some(dir) = matches.get_one::<String>("dir") {
+        eprintln!(
+            "{} `--dir` is deprecated, use `ogito new <template> <project-name>` instead",
+            style("⚠️").yellow()
+        );
+        return Ok(dir.clone());
+    }
+
+    if !console::user_attended() {
+        return Ok(default.to_string());
+    }
+
+    Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Project name")
+        .default(default.to_string())
+        .validate_with(|input: &String| -> Result<(), String> {
+            sanitize_dir(input).map(|_| ()).map_err(|e| e.to_string())
+        })
+        .interact_text()
+        .map_err(|e| eyre!("Failed to interact with user: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_url() {
+        assert_eq!(host_url("github"), "https://github.com");
+        assert_eq!(host_url("bitbucket"), "https://bitbucket.org");
+        assert_eq!(host_url("gitlab.com"), "https://gitlab.com");
+        assert_eq!(host_url("git.example.org"), "https://git.example.org");
+        assert_eq!(
+            host_url("http://git.internal:8080/"),
+            "http://git.internal:8080"
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use crate::file::{
+    merge::{plan_merge, Outcome, Strategy},
+    placeholder::{find_placeholders, render_placeholders, PROJECT_NAME},
+    project::{plan, rename_project},
+};
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use indicatif::HumanBytes;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Returns the project name generation derives from the destination directory.
+pub fn dir_name(destination: &Path) -> Result<&str> {
+    destination
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| eyre!("Invalid directory name: {}", destination.display()))
+}
+
+/// Describes what generating the project staged in `staged` into `destination`
+/// would do, without touching `destination`. With `merge`, the project is
+/// merged into the existing files instead of replacing them. `placeholders`
+/// tells whether the template declares placeholders to render.
+pub fn print_preview(
+    staged: &Path,
+    destination: &Path,
+    project_name: &str,
+    reset_metadata: bool,
+    merge: Option<Strategy>,
+    placeholders: bool,
+) -> Result<()> {
+    println!(
+        "{} Dry run: ogito new would {} {}",
+        style("📋").cyan(),
+        if merge.is_some() {
+            "merge into"
+        } else {
+            "create"
+        },
+        style(destination.display()).bold()
+    );
+
+    let files = list_files(staged)?;
+    let mut total_bytes = 0;
+    for (relative, size) in &files {
+        let depth = relative.components().count() - 1;
+        let name = relative.file_name().unwrap_or_default().to_string_lossy();
+        match size {
+            Some(size) => {
+                total_bytes += size;
+                println!("  {}{} ({})", "  ".repeat(depth), name, HumanBytes(*size));
+            }
+            None => println!("  {}{}/", "  ".repeat(depth), style(name).blue()),
+        }
+    }
+    let file_count = files.iter().filter(|(_, size)| size.is_some()).count();
+    println!("{} files, {}", file_count, HumanBytes(total_bytes));
+
+    let relative = |paths: Vec<PathBuf>| -> Vec<PathBuf> {
+        paths
+            .into_iter()
+            .filter_map(|p| p.strip_prefix(staged).ok().map(Path::to_path_buf))
+            .collect()
+    };
+    let renamed = relative(
+        plan(staged, project_name, reset_metadata)?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect(),
+    );
+    let rendered = if placeholders {
+        relative(find_placeholders(staged)?)
+    } else {
+        Vec::new()
+    };
+
+    match merge {
+        Some(strategy) => {
+            // Compared as they would be written, so renamed files match
+            if placeholders {
+                render_placeholders(staged, project_name)?;
+            }
+            rename_project(staged, project_name, reset_metadata)?;
+            print_merge_preview(staged, destination, strategy)?;
+        }
+        None => {
+            let (overwritten, removed) = conflicts(&files, destination)?;
+            print_section("⚠️ Existing files that would be overwritten:", &overwritten);
+            print_section("🗑️ Existing files that would be removed:", &removed);
+        }
+    }
+
+    print_section(
+        &format!("🏷️ Project files that would be renamed to \"{project_name}\":"),
+        &renamed,
+    );
+    print_section(
+        &format!("🔤 {PROJECT_NAME} would be replaced with \"{project_name}\" in:"),
+        &rendered,
+    );
+
+    println!("\n(dry-run) No files were written.");
+    Ok(())
+}
+
+/// What merging a project into existing files would do to them, by section.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct MergePreview {
+    overwritten: Vec<PathBuf>,
+    kept_both: Vec<PathBuf>,
+    kept: Vec<PathBuf>,
+    asked: Vec<PathBuf>,
+}
+
+/// Lists what merging with `strategy` would do to the existing files. Merging
+/// never removes anything.
+fn print_merge_preview(staged: &Path, destination: &Path, strategy: Strategy) -> Result<()> {
+    let preview = merge_preview(staged, destination, strategy)?;
+    print_section(
+        "⚠️ Existing files that would be overwritten (previous versions go to the trash):",
+        &preview.overwritten,
+    );
+    print_section(
+        "± Existing files the template's version would be written next to:",
+        &preview.kept_both,
+    );
+    print_section("➖ Existing files that would be kept:", &preview.kept);
+    print_section(
+        "❓ Existing files you would be asked about:",
+        &preview.asked,
+    );
+    Ok(())
+}
+
+fn merge_preview(staged: &Path, destination: &Path, strategy: Strategy) -> Result<MergePreview> {
+    let mut preview = MergePreview::default();
+    let plan = plan_merge(staged, destination, strategy, &mut |relative, _, _| {
+        preview.asked.push(relative.to_path_buf());
+        Ok(Strategy::Skip)
+    })?;
+
+    for (path, outcome) in plan.outcomes {
+        match outcome {
+            Outcome::Overwritten => preview.overwritten.push(path),
+            Outcome::KeptBoth(copy) => {
+                let copy = copy.strip_prefix(destination).unwrap_or(&copy);
+                preview.kept_both.push(PathBuf::from(format!(
+                    "{} -> {}",
+                    path.display(),
+                    copy.display()
+                )));
+            }
+            Outcome::Skipped if !preview.asked.contains(&path) => preview.kept.push(path),
+            _ => {}
+        }
+    }
+    Ok(preview)
+}
+
+fn print_section(title: &str, paths: &[PathBuf]) {
+    if paths.is_empty() {
+        return;
+    }
+    println!("\n{title}");
+    for path in paths {
+        println!("  - {}", path.display());
+    }
+}
+
+/// Lists every entry below `root` in depth-first order, with the size of files
+/// and `None` for directories. The contents of `.git` are not listed.
+fn list_files(root: &Path) -> Result<Vec<(PathBuf, Option<u64>)>> {
+    let mut files = Vec::new();
+    collect(root, root, &mut files)?;
+    Ok(files)
+}
+
+fn collect(root: &Path, dir: &Path, files: &mut Vec<(PathBuf, Option<u64>)>) -> Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let relative = path.strip_prefix(root)?.to_path_buf();
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            files.push((relative, None));
+            if entry.file_name() != ".git" {
+                collect(root, &path, files)?;
+            }
+        } else {
+            files.push((relative, Some(metadata.len())));
+        }
+    }
+    Ok(())
+}
+
+/// Splits the files already in `destination` into those the template would
+/// overwrite and those that would be removed, since generation replaces the
+/// whole directory.
+fn conflicts(
+    files: &[(PathBuf, Option<u64>)],
+    destination: &Path,
+) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let (mut overwritten, mut removed) = (Vec::new(), Vec::new());
+    if !destination.is_dir() {
+        return Ok((overwritten, removed));
+    }
+
+    for (relative, size) in list_files(destination)? {
+        if size.is_none() {
+            continue;
+        }
+        if files
+            .iter()
+            .any(|(path, size)| *path == relative && size.is_some())
+        {
+            overwritten.push(relative);
+        } else {
+            removed.push(relative);
+        }
+    }
+    Ok((overwritten, removed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_conflicts() -> Result<()> {
+        let staged = tempdir()?;
+        let destination = tempdir()?;
+        fs::create_dir(staged.path().join("src"))?;
+        fs::write(staged.path().join("src/main.rs"), "fn main() {}")?;
+        fs::write(staged.path().join("README.md"), "")?;
+        fs::create_dir(destination.path().join("src"))?;
+        fs::write(destination.path().join("src/main.rs"), "")?;
+        fs::write(destination.path().join("notes.txt"), "")?;
+
+        let files = list_files(staged.path())?;
+        assert_eq!(
+            files,
+            vec![
+                (PathBuf::from("README.md"), Some(0)),
+                (PathBuf::from("src"), None),
+                (PathBuf::from("src/main.rs"), Some(12)),
+            ]
+        );
+
+        let (overwritten, removed) = conflicts(&files, destination.path())?;
+        assert_eq!(overwritten, vec![PathBuf::from("src/main.rs")]);
+        assert_eq!(removed, vec![PathBuf::from("notes.txt")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_preview_never_removes() -> Result<()> {
+        let staged = tempdir()?;
+        let destination = tempdir()?;
+        fs::write(staged.path().join("README.md"), "# new\n")?;
+        fs::write(staged.path().join("Cargo.toml"), "[package]\n")?;
+        fs::write(destination.path().join("README.md"), "# old\n")?;
+        fs::write(destination.path().join("Cargo.toml"), "[package]\n")?;
+        fs::write(destination.path().join("notes.txt"), "")?;
+
+        let preview = merge_preview(staged.path(), destination.path(), Strategy::Skip)?;
+        assert_eq!(
+            preview,
+            MergePreview {
+                kept: vec![PathBuf::from("README.md")],
+                ..Default::default()
+            }
+        );
+
+        let preview = merge_preview(staged.path(), destination.path(), Strategy::KeepBoth)?;
+        assert_eq!(
+            preview.kept_both,
+            vec![PathBuf::from("README.md -> README.md.ogito-new")]
+        );
+
+        let preview = merge_preview(staged.path(), destination.path(), Strategy::Prompt)?;
+        assert_eq!(preview.asked, vec![PathBuf::from("README.md")]);
+        assert!(preview.kept.is_empty() && preview.overwritten.is_empty());
+
+        let preview = merge_preview(staged.path(), destination.path(), Strategy::Overwrite)?;
+        assert_eq!(preview.overwritten, vec![PathBuf::from("README.md")]);
+        assert_eq!(
+            fs::read_to_string(destination.path().join("README.md"))?,
+            "# old\n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use crate::file::cache::get_manifest_path;
+use crate::manifest::ManifestFile;
+use crate::registry::{self, is_valid_registry_name, Registry, RegistryKind, DEFAULT_TTL};
+use clap::ArgMatches;
+use color_eyre::{eyre::eyre, Result};
+use comfy_table::{Cell, ContentArrangement, Table};
+use console::style;
+
+pub async fn run(matches: &ArgMatches) -> Result<()> {
+    let config_path = get_manifest_path()?;
+    let mut config = ManifestFile::load(&config_path)?;
+
+    match matches.subcommand() {
+        Some(("add", m)) => {
+            let name = m
+                .get_one::<String>("name")
+                .ok_or_else(|| eyre!("Name is required"))?;
+            let url = m
+                .get_one::<String>("url")
+                .ok_or_else(|| eyre!("URL is required"))?;
+
+            if !is_valid_registry_name(name) {
+                return Err(eyre!(
+                    "Invalid registry name: '{}'. Use letters, digits, '-' or '_'",
+                    name
+                ));
+            }
+            if config.content.registries.contains_key(name) {
+                return Err(eyre!("Registry '{}' already exists", name));
+            }
+
+            let registry = Registry {
+                url: url.clone(),
+                kind: m
+                    .get_one::<String>("type")
+                    .map(|kind| RegistryKind::parse(kind))
+                    .transpose()?,
+                ttl: m.get_one::<u64>("ttl").copied(),
+            };
+            let index = registry::sync(name, &registry).await?;
+
+            config.content.add_registry(name.clone(), registry);
+            config.save()?;
+            println!(
+                "{} Added registry '{}' with {} template(s)",
+                style("✅").green(),
+                name,
+                index.templates.len()
+            );
+        }
+        Some(("remove", m)) => {
+            let name = m
+                .get_one::<String>("name")
+                .ok_or_else(|| eyre!("Name is required"))?;
+            if config.content.remove_registry(name).is_none() {
+                return Err(eyre!("Registry '{}' not found", name));
+            }
+            config.save()?;
+            println!("🗑️ Registry '{name}' removed.");
+        }
+        Some(("list", _)) => {
+            if config.content.registries.is_empty() {
+                println!("No registries configured.");
+                return Ok(());
+            }
+
+            let mut table = Table::new();
+            table.set_header(vec!["Name", "URL", "TTL"]);
+            for (name, registry) in &config.content.registries {
+                let ttl = registry.ttl.unwrap_or(DEFAULT_TTL);
+                table.add_row(vec![
+                    Cell::new(name),
+                    Cell::new(&registry.url),
+                    Cell::new(format!("{ttl}s")),
+                ]);
+            }
+            table.set_content_arrangement(ContentArrangement::Dynamic);
+            println!("{table}");
+        }
+        Some(("sync", m)) => {
+            let names: Vec<String> = match m.get_one::<String>("name") {
+                Some(name) => vec![name.clone()],
+                None => config.content.registries.keys().cloned().collect(),
+            };
+            for name in names {
+                let registry = config
+                    .content
+                    .registries
+                    .get(&name)
+                    .ok_or_else(|| eyre!("Registry '{}' not found", name))?;
+                let index = registry::sync(&name, registry).await?;
+                println!(
+                    "{} Synced registry '{}': {} template(s)",
+                    style("✅").green(),
+                    name,
+                    index.templates.len()
+                );
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
@@ -1,11 +1,11 @@
 use crate::cmd::clear::dir::{compute_dir_stats, list_dir_entries};
-use crate::file::cache::get_cache_root;
+use crate::file::cache::{get_manifest_path, get_templates_dir};
 use crate::manifest::{Manifest, ManifestFile};
+use crate::trash::{print_undo_hint, Trash};
 use clap::ArgMatches;
-use color_eyre::Result;
-use dialoguer::Confirm;
+use color_eyre::{eyre::eyre, Result};
+use dialoguer::{theme::ColorfulTheme, Confirm, MultiSelect};
 use indicatif::{HumanBytes, ProgressBar, ProgressStyle};
-use std::fs;
 use std::path::Path;
 
 pub fn run(matches: &ArgMatches) -> Result<()> {
@@ -14,36 +14,84 @@ pub fn run(matches: &ArgMatches) -> Result<()> {
     let quiet = matches.get_flag("quiet");
     let all = matches.get_flag("all");
 
-    let templates_dir = get_cache_root().join("templates");
-
-    if !templates_dir.exists() {
-        if !quiet {
-            println!("Template directory does not exist. Nothing to remove.");
-        }
-        return Ok(());
-    }
+    let templates_dir = get_templates_dir()?;
 
     if all {
+        if !templates_dir.exists() {
+            if !quiet {
+                println!("Template directory does not exist. Nothing to remove.");
+            }
+            return Ok(());
+        }
         handle_remove_all(&templates_dir, dry_run, force, quiet)
     } else {
-        let template_names: Vec<String> = matches
+        let mut template_names: Vec<String> = matches
             .get_many::<String>("TEMPLATES")
             .unwrap_or_default()
             .map(String::from)
             .collect();
 
         if template_names.is_empty() {
-            println!("No template names provided. Use --all to remove all templates.");
-            return Ok(());
+            if !console::user_attended() {
+                println!("No template names provided. Use --all to remove all templates.");
+                return Ok(());
+            }
+
+            template_names = select_templates(&templates_dir)?;
+            if template_names.is_empty() {
+                println!("No templates selected.");
+                return Ok(());
+            }
         }
 
         handle_remove_specific(&templates_dir, &template_names, dry_run, force, quiet)
     }
 }
 
+fn select_templates(templates_dir: &Path) -> Result<Vec<String>> {
+    let config_path = get_manifest_path()?;
+    let manifest = Manifest::read(&config_path)?;
+    let templates = &manifest.templates;
+
+    if templates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Group by category, listing untagged templates last
+    let mut names: Vec<&String> = templates.keys().collect();
+    names.sort_by_key(|name| {
+        let category = templates[*name].category();
+        (category.is_none(), category)
+    });
+
+    let mut items = Vec::new();
+    for name in &names {
+        let template = &templates[*name];
+        let (_, bytes) = compute_dir_stats(&templates_dir.join(name))?;
+        let category = template
+            .category()
+            .map(|category| format!("[{category}] "))
+            .unwrap_or_default();
+        let alias = if template.aliases.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", template.aliases.join(", "))
+        };
+        items.push(format!("{category}{name}{alias} - {}", HumanBytes(bytes)));
+    }
+
+    let selection = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select the templates to remove (space to select, enter to confirm)")
+        .items(&items)
+        .interact()
+        .map_err(|e| eyre!("Failed to interact with user: {}", e))?;
+
+    Ok(selection.into_iter().map(|i| names[i].clone()).collect())
+}
+
 fn handle_remove_all(templates_dir: &Path, dry_run: bool, force: bool, quiet: bool) -> Result<()> {
     let (file_count, total_bytes) = compute_dir_stats(templates_dir)?;
-    let config_path = get_cache_root().join("template.toml");
+    let config_path = get_manifest_path()?;
     let mut config = ManifestFile::load(&config_path)?;
 
     if file_count == 0 && total_bytes == 0 {
@@ -91,11 +139,18 @@ fn handle_remove_all(templates_dir: &Path, dry_run: bool, force: bool, quiet: bo
         }
     }
 
-    fs::remove_dir_all(templates_dir)?;
+    let mut entry = Trash::open()?.create_entry("remove --all")?;
+    for path in list_dir_entries(templates_dir)? {
+        entry.store(&path)?;
+    }
+    for (name, template) in &config.content.templates {
+        entry.store_template(name, template.clone())?;
+    }
     Manifest::clear(&mut config.content);
 
     if !quiet {
         println!("🗑️ All templates removed successfully.");
+        print_undo_hint(&entry);
     }
 
     config.save()?;
@@ -113,7 +168,7 @@ fn handle_remove_specific(
     let mut targets = Vec::new();
     let mut total_bytes = 0;
     let mut total_files = 0;
-    let config_path = get_cache_root().join("template.toml");
+    let config_path = get_manifest_path()?;
     let mut config = ManifestFile::load(&config_path)?;
 
     for name in template_names {
@@ -124,8 +179,9 @@ fn handle_remove_specific(
                 let (file_count, bytes) = compute_dir_stats(&path)?;
                 total_files += file_count;
                 total_bytes += bytes;
-                targets.push((name.clone(), path));
             }
+            // Linked and not yet cloned templates only live in the manifest
+            targets.push((name.clone(), path));
         }
     }
 
@@ -180,9 +236,15 @@ fn handle_remove_specific(
         Some(pb)
     };
 
+    let names: Vec<&str> = targets.iter().map(|(name, _)| name.as_str()).collect();
+    let mut entry = Trash::open()?.create_entry(&format!("remove {}", names.join(" ")))?;
     for (name, path) in &targets {
-        fs::remove_dir_all(path)?;
-        config.remove_template(name);
+        if path.exists() {
+            entry.store(path)?;
+        }
+        if let Some(template) = config.remove_template(name) {
+            entry.store_template(name, template)?;
+        }
 
         if let Some(pb) = &spinner {
             pb.set_message(format!("Removed '{name}'"));
@@ -195,6 +257,7 @@ fn handle_remove_specific(
 
     if !quiet {
         println!("🗑️ {} template(s) removed successfully.", targets.len());
+        print_undo_hint(&entry);
     }
 
     config.save()?;
@@ -0,0 +1,61 @@
+use crate::cmd::add::validate_name;
+use crate::file::cache::{get_manifest_path, get_templates_dir};
+use crate::manifest::ManifestFile;
+use clap::ArgMatches;
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use std::fs;
+
+pub fn run(matches: &ArgMatches) -> Result<()> {
+    let old = matches
+        .get_one::<String>("old")
+        .ok_or_else(|| eyre!("Template name is required"))?;
+    let new = matches
+        .get_one::<String>("new")
+        .ok_or_else(|| eyre!("New name is required"))?;
+
+    validate_name(new)?;
+
+    let config_path = get_manifest_path()?;
+    let mut config = ManifestFile::load(&config_path)?;
+    let actual_name = config
+        .content
+        .find(old)
+        .cloned()
+        .ok_or_else(|| eyre!("Template '{}' not found", old))?;
+
+    config.content.rename_template(&actual_name, new)?;
+
+    let templates_dir = get_templates_dir()?;
+    let from = templates_dir.join(&actual_name);
+    let to = templates_dir.join(new);
+    let moved = from.exists();
+    if moved {
+        if to.exists() {
+            return Err(eyre!(
+                "Directory {} already exists. Remove it before renaming.",
+                to.display()
+            ));
+        }
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // A single rename keeps the move atomic on the same filesystem
+        fs::rename(&from, &to)?;
+    }
+
+    if let Err(e) = config.save() {
+        if moved {
+            fs::rename(&to, &from)?;
+        }
+        return Err(e);
+    }
+
+    println!(
+        "{} Renamed template '{}' to '{}'",
+        style("✅").green(),
+        actual_name,
+        new
+    );
+    Ok(())
+}
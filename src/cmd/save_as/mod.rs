@@ -0,0 +1,143 @@
+use crate::cmd::add::{fetch_template, validate_alias, validate_name};
+use crate::cmd::clear::dir::compute_dir_stats;
+use crate::file::{
+    cache::{get_manifest_path, get_templates_dir},
+    placeholder::{find_occurrences, insert_placeholder, PROJECT_NAME},
+};
+use crate::manifest::{normalize_tags, ManifestFile, Template};
+use clap::ArgMatches;
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use dialoguer::Confirm;
+use indicatif::HumanBytes;
+use std::fs;
+use std::path::Path;
+
+pub async fn run(matches: &ArgMatches) -> Result<()> {
+    let name = matches
+        .get_one::<String>("name")
+        .ok_or_else(|| eyre!("Name is required"))?;
+    let from = matches
+        .get_one::<String>("from")
+        .ok_or_else(|| eyre!("Source directory is required"))?;
+    let force = matches.get_flag("force");
+
+    validate_name(name)?;
+
+    let source = fs::canonicalize(from)
+        .map_err(|e| eyre!("Cannot read source directory '{}': {}", from, e))?;
+    if !source.is_dir() {
+        return Err(eyre!("'{}' is not a directory", from));
+    }
+
+    let templates_dir = get_templates_dir()?;
+    if templates_dir.exists() && source.starts_with(fs::canonicalize(&templates_dir)?) {
+        return Err(eyre!(
+            "Cannot save a template from inside the template store"
+        ));
+    }
+
+    let config_path = get_manifest_path()?;
+    let mut config = ManifestFile::load(&config_path)?;
+    if config.content.templates.contains_key(name) && !force {
+        return Err(eyre!(
+            "⚠️ {} already exists. Use --force to overwrite it.",
+            name
+        ));
+    }
+
+    let placeholder = if matches.get_flag("placeholder") {
+        let dir_name = source
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| eyre!("Could not determine the project name"))?;
+        Some(dir_name.to_string())
+    } else {
+        None
+    };
+
+    let mut template = Template {
+        description: matches.get_one::<String>("description").cloned(),
+        aliases: matches
+            .get_many::<String>("alias")
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
+        url: source
+            .to_str()
+            .ok_or_else(|| eyre!("Invalid path: contains non-UTF-8 characters"))?
+            .to_string(),
+        git_ref: None,
+        link: false,
+        project_name: None,
+        tags: normalize_tags(matches.get_many::<String>("tag").unwrap_or_default())?,
+    };
+
+    for alias in &template.aliases {
+        validate_alias(alias)?;
+    }
+    config.content.check_conflicts(name, &template)?;
+    fetch_template(name, &template, force).await?;
+
+    let stored = templates_dir.join(name);
+    if let Some(project_name) = placeholder {
+        if confirm_placeholder(&stored, &project_name)? {
+            insert_placeholder(&stored, &project_name)?;
+            template.project_name = Some(project_name);
+        }
+    }
+    config.add_template(name.clone(), template);
+    config.save()?;
+
+    let (file_count, total_bytes) = compute_dir_stats(&stored)?;
+    println!(
+        "{} Saved {} as template '{}' ({} files, {})",
+        style("✅").green(),
+        source.display(),
+        name,
+        file_count,
+        HumanBytes(total_bytes)
+    );
+
+    Ok(())
+}
+
+/// Lists the lines of the snapshot in `dir` where `project_name` would be
+/// replaced with the placeholder, and asks whether to replace them.
+fn confirm_placeholder(dir: &Path, project_name: &str) -> Result<bool> {
+    let found = find_occurrences(dir, project_name)?;
+    if found.is_empty() {
+        println!(
+            "{} '{}' does not appear in the project, no placeholder inserted",
+            style("ℹ️").cyan(),
+            project_name
+        );
+        return Ok(false);
+    }
+
+    println!(
+        "{} '{}' would be replaced with {} in:",
+        style("🔤").cyan(),
+        project_name,
+        PROJECT_NAME
+    );
+    for (path, lines) in &found {
+        println!(
+            "  {}",
+            style(path.strip_prefix(dir).unwrap_or(path).display()).bold()
+        );
+        for (number, line) in lines {
+            println!("    {number}: {line}");
+        }
+    }
+
+    // --placeholder already asked for it when nobody is there to answer
+    if !console::user_attended() {
+        return Ok(true);
+    }
+    Confirm::new()
+        .with_prompt("Replace these occurrences?")
+        .default(true)
+        .interact()
+        .map_err(|e| eyre!("Failed to interact with user: {}", e))
+}
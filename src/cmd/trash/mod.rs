@@ -0,0 +1,62 @@
+use crate::cmd::clear::dir::compute_dir_stats;
+use crate::cmd::undo::restore;
+use crate::trash::{retention, Trash};
+use clap::ArgMatches;
+use color_eyre::{eyre::eyre, Result};
+use comfy_table::{Cell, ContentArrangement, Table};
+use dialoguer::Confirm;
+use indicatif::{HumanBytes, HumanDuration};
+
+pub fn run(matches: &ArgMatches) -> Result<()> {
+    let trash = Trash::open()?;
+    trash.purge_older_than(retention())?;
+
+    match matches.subcommand() {
+        Some(("list", _)) => {
+            let entries = trash.entries()?;
+            if entries.is_empty() {
+                println!("The trash is empty.");
+                return Ok(());
+            }
+
+            let mut table = Table::new();
+            table.set_header(vec!["ID", "Operation", "Deleted", "Items", "Size"]);
+            for entry in &entries {
+                let (_, bytes) = compute_dir_stats(entry.dir())?;
+                table.add_row(vec![
+                    Cell::new(&entry.id),
+                    Cell::new(&entry.operation),
+                    Cell::new(format!("{} ago", HumanDuration(entry.age()))),
+                    Cell::new(entry.paths.len() + entry.templates.len()),
+                    Cell::new(HumanBytes(bytes)),
+                ]);
+            }
+            table.set_content_arrangement(ContentArrangement::Dynamic);
+            println!("{table}");
+        }
+        Some(("restore", m)) => {
+            let id = m
+                .get_one::<String>("id")
+                .ok_or_else(|| eyre!("Entry ID is required"))?;
+            let entry = trash.find(id)?;
+            restore(&trash, &entry)?;
+        }
+        Some(("empty", m)) => {
+            if !m.get_flag("force") {
+                let confirmed = Confirm::new()
+                    .with_prompt("Permanently delete everything in the trash?")
+                    .default(false)
+                    .interact()?;
+                if !confirmed {
+                    println!("🛑 Operation cancelled.");
+                    return Ok(());
+                }
+            }
+            let count = trash.empty()?;
+            println!("🗑️ Deleted {count} trash entry(s).");
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::file::cache::get_manifest_path;
+use crate::manifest::{Manifest, ManifestFile};
+use crate::trash::{retention, Trash, TrashEntry};
+use clap::ArgMatches;
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use indicatif::HumanDuration;
+
+pub fn run(_matches: &ArgMatches) -> Result<()> {
+    let trash = Trash::open()?;
+    trash.purge_older_than(retention())?;
+    let entry = trash
+        .entries()?
+        .into_iter()
+        .next()
+        .ok_or_else(|| eyre!("Nothing to undo: the trash is empty"))?;
+    restore(&trash, &entry)
+}
+
+/// Restores `entry`, adding its templates back to the manifest.
+pub fn restore(trash: &Trash, entry: &TrashEntry) -> Result<()> {
+    if entry.templates.is_empty() {
+        trash.restore(entry, &mut Manifest::default())?;
+    } else {
+        let mut config = ManifestFile::load(&get_manifest_path()?)?;
+        trash.restore(entry, &mut config.content)?;
+        config.save()?;
+    }
+
+    println!(
+        "{} Undid '{}' from {} ago",
+        style("↩️").cyan(),
+        style(&entry.operation).bold(),
+        HumanDuration(entry.age())
+    );
+    for trashed in &entry.paths {
+        println!("  - {}", trashed.original.display());
+    }
+    for name in entry.templates.keys() {
+        println!("  - template '{name}'");
+    }
+    Ok(())
+}
@@ -1,11 +1,14 @@
-use crate::{file::cache::get_cache_root, manifest::ManifestFile, progress::create_spinner};
+use crate::{
+    file::cache::{get_manifest_path, get_templates_dir},
+    manifest::ManifestFile,
+    progress::create_spinner,
+};
 use clap::ArgMatches;
 use color_eyre::{eyre::eyre, Result};
 use console::style;
 
 pub async fn run(matches: &ArgMatches) -> Result<()> {
-    let cache_path = get_cache_root();
-    let config_path = cache_path.join("template.toml");
+    let config_path = get_manifest_path()?;
 
     if !config_path.exists() {
         return Err(eyre!(
@@ -138,6 +141,31 @@ async fn update_template(
 ) -> Result<()> {
     use crate::regex::is_valid_url;
 
+    if template.link {
+        if !quiet {
+            println!(
+                "{} Template '{}' is linked to {} and always up to date",
+                style("🔗").cyan(),
+                template_name,
+                template.url
+            );
+        }
+        return Ok(());
+    }
+
+    if template.is_local() {
+        crate::cmd::add::fetch_template(template_name, template, true).await?;
+        if !quiet {
+            println!(
+                "{} Template re-snapshotted from {}: {}",
+                style("✅").green(),
+                template.url,
+                template_name
+            );
+        }
+        return Ok(());
+    }
+
     if !is_valid_url(&template.url)? {
         return Err(eyre!(
             "Invalid URL for template '{}': {}",
@@ -146,7 +174,7 @@ async fn update_template(
         ));
     }
 
-    let templates_dir = get_cache_root().join("templates");
+    let templates_dir = get_templates_dir()?;
     let destination = templates_dir.join(template_name);
 
     if !destination.exists() {
@@ -166,11 +194,22 @@ async fn update_template(
         .ok_or_else(|| eyre!("Failed to convert destination path to string"))?
         .to_string();
 
-    let clone_config =
-        crate::fetch::config::Config::from(&dest_string, crate::mode::Mode::Git, force, true, None);
+    let clone_config = crate::fetch::config::Config::from(
+        &dest_string,
+        crate::mode::Mode::Git,
+        force,
+        true,
+        template.git_ref.as_ref(),
+    );
 
-    // Use force_clone to remove existing directory and re-clone
-    crate::clone::force_clone(&template.url, &dest_string, &clone_config).await?;
+    // Re-clone, keeping the previous checkout in the trash
+    crate::clone::force_clone(
+        &template.url,
+        &dest_string,
+        &clone_config,
+        &format!("update {template_name}"),
+    )
+    .await?;
 
     if !quiet {
         println!(
@@ -196,12 +235,19 @@ mod tests {
             "test-template".to_string(),
             Template {
                 description: Some("Test template".to_string()),
-                alias: Some("test".to_string()),
+                aliases: vec!["test".to_string()],
                 url: "https://example.com/test.git".to_string(),
+                git_ref: None,
+                link: false,
+                project_name: None,
+                tags: Vec::new(),
             },
         );
 
-        let manifest = Manifest { templates };
+        let manifest = Manifest {
+            templates,
+            ..Default::default()
+        };
 
         // Test finding by exact name
         assert_eq!(
@@ -1,3 +1,4 @@
+use crate::file::exclude::ExcludeOptions;
 use crate::mode::Mode;
 
 #[derive(Debug)]
@@ -7,6 +8,8 @@ pub struct Config<'a> {
     pub force: bool,
     pub keep_history: bool,
     pub branch: Option<&'a String>,
+    /// Exclusion rules applied to the generated files, if any.
+    pub exclude: Option<ExcludeOptions>,
 }
 
 impl<'a> Config<'a> {
@@ -23,13 +26,20 @@ impl<'a> Config<'a> {
             force,
             keep_history,
             branch,
+            exclude: None,
         }
     }
+
+    pub fn with_exclude(mut self, exclude: ExcludeOptions) -> Self {
+        self.exclude = Some(exclude);
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Config;
+    use crate::file::exclude::ExcludeOptions;
     use crate::mode::Mode;
 
     #[test]
@@ -43,4 +53,17 @@ mod tests {
         assert!(!config.force);
         assert!(!config.keep_history);
     }
+
+    #[test]
+    fn test_config_with_exclude() {
+        let dir = String::from("dir");
+        let config = Config::from(&dir, Mode::Tar, false, false, None);
+        assert!(config.exclude.is_none());
+
+        let config = config.with_exclude(ExcludeOptions {
+            patterns: vec!["*.md".to_string()],
+            features: Vec::new(),
+        });
+        assert_eq!(config.exclude.unwrap().patterns, vec!["*.md".to_string()]);
+    }
 }
@@ -1,4 +1,11 @@
-use std::path::PathBuf;
+use color_eyre::{eyre::eyre, Result};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable that relocates the ogito home directory.
+pub const HOME_ENV: &str = "OGITO_HOME";
+
+static HOME_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
 
 pub struct CacheMetadata {
     pub owner: String,
@@ -23,19 +30,66 @@ pub struct CacheConfig {
     pub archive_path: PathBuf,
 }
 
-pub fn get_cache_root() -> PathBuf {
-    dirs::cache_dir()
-        .expect("Failed to get cache directory")
-        .join("ogito")
+/// Overrides the home directory for the rest of the process (`--home`).
+pub fn set_home(path: &Path) {
+    let _ = HOME_OVERRIDE.set(path.to_path_buf());
+}
+
+/// Returns the ogito home holding the manifest, templates and archive cache.
+///
+/// Resolved from `--home`, then `OGITO_HOME`, then `<cache dir>/ogito`.
+pub fn get_cache_root() -> Result<PathBuf> {
+    let env_home = std::env::var_os(HOME_ENV).filter(|value| !value.is_empty());
+    resolve_root(
+        HOME_OVERRIDE.get().cloned(),
+        env_home.map(PathBuf::from),
+        dirs::cache_dir(),
+    )
+}
+
+fn resolve_root(
+    override_home: Option<PathBuf>,
+    env_home: Option<PathBuf>,
+    cache_dir: Option<PathBuf>,
+) -> Result<PathBuf> {
+    let root = match override_home.or(env_home) {
+        Some(home) => home,
+        None => cache_dir
+            .ok_or_else(|| {
+                eyre!(
+                    "Could not determine a cache directory. Set {} or pass --home.",
+                    HOME_ENV
+                )
+            })?
+            .join("ogito"),
+    };
+
+    if root.is_absolute() {
+        Ok(root)
+    } else {
+        Ok(std::env::current_dir()?.join(root))
+    }
+}
+
+pub fn get_cache_dir() -> Result<PathBuf> {
+    Ok(get_cache_root()?.join("cache"))
+}
+
+pub fn get_manifest_path() -> Result<PathBuf> {
+    Ok(get_cache_root()?.join("template.toml"))
 }
 
-pub fn get_cache_dir() -> PathBuf {
-    get_cache_root().join("cache")
+pub fn get_templates_dir() -> Result<PathBuf> {
+    Ok(get_cache_root()?.join("templates"))
+}
+
+pub fn get_trash_dir() -> Result<PathBuf> {
+    Ok(get_cache_root()?.join("trash"))
 }
 
 impl CacheConfig {
-    pub fn new(cache_metadata: &CacheMetadata) -> Self {
-        let cache_path = get_cache_dir();
+    pub fn new(cache_metadata: &CacheMetadata) -> Result<Self> {
+        let cache_path = get_cache_dir()?;
         let cache_hash_path = cache_path
             .join(&cache_metadata.owner)
             .join(&cache_metadata.repo)
@@ -44,9 +98,43 @@ impl CacheConfig {
 
         let archive_path = cache_hash_path.join("archive.tar.gz");
 
-        Self {
+        Ok(Self {
             cache_hash_path,
             archive_path,
-        }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_root_precedence() -> Result<()> {
+        let base = std::env::temp_dir();
+        let flag = base.join("flag");
+        let env = base.join("env");
+        let cache = base.join("cache");
+
+        assert_eq!(
+            resolve_root(Some(flag.clone()), Some(env.clone()), Some(cache.clone()))?,
+            flag
+        );
+        assert_eq!(
+            resolve_root(None, Some(env.clone()), Some(cache.clone()))?,
+            env
+        );
+        assert_eq!(
+            resolve_root(None, None, Some(cache.clone()))?,
+            cache.join("ogito")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_root_without_cache_dir() {
+        let err = resolve_root(None, None, None).unwrap_err();
+        assert!(err.to_string().contains(HOME_ENV));
     }
 }
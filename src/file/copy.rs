@@ -1,8 +1,21 @@
+use crate::file::exclude::ExcludeRules;
 use color_eyre::eyre::Result;
-use fs_extra::dir::{copy, CopyOptions};
+use ignore::WalkBuilder;
+use std::fs;
 use std::path::Path;
 
-pub fn create_template<P: AsRef<Path>>(source: P, destination: P) -> Result<()> {
+/// Per-template ignore file, using `.gitignore` syntax.
+pub const IGNORE_FILE: &str = ".ogitoignore";
+
+/// Directories never worth capturing in a template.
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target"];
+
+/// Copies a template into `destination`, leaving out files excluded by `rules`.
+pub fn create_template<P: AsRef<Path>>(
+    source: P,
+    destination: P,
+    rules: &ExcludeRules,
+) -> Result<()> {
     let source_path = source.as_ref();
     let dest_path = destination.as_ref();
 
@@ -19,11 +32,113 @@ pub fn create_template<P: AsRef<Path>>(source: P, destination: P) -> Result<()>
         ));
     }
 
-    let mut options = CopyOptions::new();
-    options.overwrite = true;
-    options.copy_inside = true;
+    copy_dir(source_path, source_path, dest_path, rules)
+}
+
+fn copy_dir(root: &Path, dir: &Path, destination: &Path, rules: &ExcludeRules) -> Result<()> {
+    fs::create_dir_all(destination)?;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let is_dir = path.is_dir();
+        let relative = path.strip_prefix(root)?;
 
-    copy(source_path, dest_path, &options)?;
+        if rules.is_excluded(relative, is_dir) {
+            continue;
+        }
 
+        let target = destination.join(entry.file_name());
+        if is_dir {
+            copy_dir(root, &path, &target, rules)?;
+        } else {
+            fs::copy(&path, &target)?;
+        }
+    }
     Ok(())
 }
+
+/// Copies `source` into `destination`, honoring `.gitignore` and `.ogitoignore`
+/// files and skipping VCS metadata and build output. Returns the number of
+/// files copied.
+pub fn copy_filtered(source: &Path, destination: &Path) -> Result<u64> {
+    if !source.is_dir() {
+        return Err(color_eyre::eyre::eyre!(
+            "Source path '{:?}' is not a directory.",
+            source
+        ));
+    }
+
+    let walker = WalkBuilder::new(source)
+        .hidden(false)
+        .require_git(false)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .filter_entry(|entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            !(is_dir && SKIPPED_DIRS.iter().any(|name| entry.file_name() == *name))
+        })
+        .build();
+
+    let mut copied = 0;
+    fs::create_dir_all(destination)?;
+    for entry in walker {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(source)?;
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+
+        let target = destination.join(relative);
+        let file_type = entry
+            .file_type()
+            .ok_or_else(|| color_eyre::eyre::eyre!("Unreadable entry: {:?}", entry.path()))?;
+        if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if file_type.is_file() {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(entry.path(), &target)?;
+            copied += 1;
+        }
+    }
+
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_copy_filtered_honors_ignore_files() -> Result<()> {
+        let source = tempdir()?;
+        let destination = tempdir()?;
+        let root = source.path();
+
+        fs::create_dir_all(root.join("src"))?;
+        fs::create_dir_all(root.join("node_modules/pkg"))?;
+        fs::create_dir_all(root.join(".git"))?;
+        fs::create_dir_all(root.join("dist"))?;
+        fs::write(root.join("src/main.rs"), "fn main() {}")?;
+        fs::write(root.join("node_modules/pkg/index.js"), "")?;
+        fs::write(root.join(".git/HEAD"), "")?;
+        fs::write(root.join("dist/out.js"), "")?;
+        fs::write(root.join("secret.env"), "")?;
+        fs::write(root.join(".gitignore"), "dist/\n")?;
+        fs::write(root.join(IGNORE_FILE), "*.env\n")?;
+
+        let copied = copy_filtered(root, destination.path())?;
+        let out = destination.path();
+
+        assert!(out.join("src/main.rs").exists());
+        assert!(out.join(".gitignore").exists());
+        assert!(!out.join("node_modules").exists());
+        assert!(!out.join(".git").exists());
+        assert!(!out.join("dist").exists());
+        assert!(!out.join("secret.env").exists());
+        assert_eq!(copied, 3);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+use crate::file::copy::IGNORE_FILE;
+use color_eyre::{eyre::eyre, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::fs;
+use std::path::Path;
+
+/// Per-invocation exclusion settings for `ogito new`.
+#[derive(Debug, Clone, Default)]
+pub struct ExcludeOptions {
+    /// Globs passed with `--exclude`.
+    pub patterns: Vec<String>,
+    /// Optional template features enabled with `--with`.
+    pub features: Vec<String>,
+}
+
+/// Decides which template files are left out of a generated project.
+///
+/// Rules use `.gitignore` syntax. A rule in `.ogitoignore` may be prefixed with
+/// `[feature]` to exclude the matching files unless `--with feature` is given,
+/// or with `[!feature]` to exclude them only when it is.
+pub struct ExcludeRules {
+    matcher: Gitignore,
+}
+
+impl ExcludeRules {
+    pub fn new(template_rules: Option<&str>, options: &ExcludeOptions) -> Result<Self> {
+        let mut builder = GitignoreBuilder::new("");
+        add_line(&mut builder, IGNORE_FILE)?;
+
+        for line in template_rules.unwrap_or_default().lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(pattern) = conditional_pattern(line, &options.features) {
+                add_line(&mut builder, pattern)?;
+            }
+        }
+
+        for pattern in &options.patterns {
+            add_line(&mut builder, pattern)?;
+        }
+
+        let matcher = builder
+            .build()
+            .map_err(|e| eyre!("Invalid exclude rules: {}", e))?;
+        Ok(Self { matcher })
+    }
+
+    /// Builds the rules from the `.ogitoignore` at the root of a template.
+    pub fn load(template_root: &Path, options: &ExcludeOptions) -> Result<Self> {
+        let ignore_file = template_root.join(IGNORE_FILE);
+        let content = if ignore_file.is_file() {
+            Some(fs::read_to_string(ignore_file)?)
+        } else {
+            None
+        };
+        Self::new(content.as_deref(), options)
+    }
+
+    /// Whether `relative`, a path relative to the template root, is excluded.
+    pub fn is_excluded(&self, relative: &Path, is_dir: bool) -> bool {
+        self.matcher
+            .matched_path_or_any_parents(relative, is_dir)
+            .is_ignore()
+    }
+
+    /// Removes every excluded file and directory below `root`.
+    pub fn prune(&self, root: &Path) -> Result<()> {
+        self.prune_dir(root, root)
+    }
+
+    fn prune_dir(&self, root: &Path, dir: &Path) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let is_dir = entry.file_type()?.is_dir();
+            let relative = path.strip_prefix(root)?;
+
+            if relative.starts_with(".git") {
+                continue;
+            }
+
+            if self.is_excluded(relative, is_dir) {
+                if is_dir {
+                    fs::remove_dir_all(&path)?;
+                } else {
+                    fs::remove_file(&path)?;
+                }
+            } else if is_dir {
+                self.prune_dir(root, &path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn add_line(builder: &mut GitignoreBuilder, line: &str) -> Result<()> {
+    builder
+        .add_line(None, line)
+        .map_err(|e| eyre!("Invalid exclude pattern '{}': {}", line, e))?;
+    Ok(())
+}
+
+/// Returns the pattern of a rule if it is active for the enabled `features`.
+fn conditional_pattern<'a>(line: &'a str, features: &[String]) -> Option<&'a str> {
+    let Some(rest) = line.strip_prefix('[') else {
+        return Some(line);
+    };
+    let Some((condition, pattern)) = rest.split_once(']') else {
+        return Some(line);
+    };
+
+    let (negated, feature) = match condition.strip_prefix('!') {
+        Some(feature) => (true, feature),
+        None => (false, condition),
+    };
+    let enabled = features.iter().any(|f| f == feature.trim());
+    (enabled == negated).then_some(pattern.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    fn options(patterns: &[&str], features: &[&str]) -> ExcludeOptions {
+        ExcludeOptions {
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+            features: features.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_template_and_cli_rules() -> Result<()> {
+        let rules = ExcludeRules::new(
+            Some("# comment\nCHANGELOG.md\n[ci] .github/\n[!docker] Dockerfile.example\n"),
+            &options(&["*.png"], &[]),
+        )?;
+
+        assert!(rules.is_excluded(Path::new(IGNORE_FILE), false));
+        assert!(rules.is_excluded(Path::new("CHANGELOG.md"), false));
+        assert!(rules.is_excluded(Path::new(".github/workflows/ci.yml"), false));
+        assert!(rules.is_excluded(Path::new("assets/logo.png"), false));
+        assert!(!rules.is_excluded(Path::new("Dockerfile.example"), false));
+        assert!(!rules.is_excluded(Path::new("src/main.rs"), false));
+
+        let rules = ExcludeRules::new(
+            Some("[ci] .github/\n[!docker] Dockerfile.example\n"),
+            &options(&[], &["ci", "docker"]),
+        )?;
+        assert!(!rules.is_excluded(Path::new(".github/workflows/ci.yml"), false));
+        assert!(rules.is_excluded(Path::new("Dockerfile.example"), false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune() -> Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join(".github/workflows"))?;
+        fs::write(root.join(".github/workflows/ci.yml"), "")?;
+        fs::write(root.join("LICENSE"), "")?;
+        fs::write(root.join("main.rs"), "")?;
+        fs::write(root.join(IGNORE_FILE), ".github/\nLICENSE\n")?;
+
+        ExcludeRules::load(root, &ExcludeOptions::default())?.prune(root)?;
+
+        let mut remaining: Vec<PathBuf> = fs::read_dir(root)?
+            .map(|e| e.map(|e| e.file_name().into()))
+            .collect::<std::io::Result<_>>()?;
+        remaining.sort();
+        assert_eq!(remaining, vec![PathBuf::from("main.rs")]);
+
+        Ok(())
+    }
+}
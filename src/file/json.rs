@@ -1,110 +1,245 @@
-use color_eyre::Result;
-use serde_json::Value;
-use std::fs;
-use std::path::Path;
-
-pub fn update_package_json_name<P: AsRef<Path>>(path: P, new_name: &str) -> Result<()> {
-    let path = path.as_ref();
-    let content = fs::read_to_string(path)?;
-    let mut package_json: Value = serde_json::from_str(&content)?;
-
-    if let Some(obj) = package_json.as_object_mut() {
-        obj.insert("name".to_string(), Value::String(new_name.to_string()));
-    }
-
-    let updated_content = serde_json::to_string_pretty(&package_json)?;
-    fs::write(path, updated_content)?;
-
-    Ok(())
+//! Edits JSON documents in place, keeping key order, indentation and the
+//! trailing newline that a round trip through `serde_json::Value` would lose.
+
+use color_eyre::{eyre::eyre, Result};
+use std::ops::Range;
+
+/// A `"key": value` pair of a JSON object, as byte ranges into the document.
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub key: String,
+    /// The quoted key.
+    pub key_span: Range<usize>,
+    /// The value, from its first to its last character.
+    pub value: Range<usize>,
 }
 
-/// Recursively finds and updates package.json files in a directory
-pub fn update_package_json_in_dir<P: AsRef<Path>>(dir_path: P, new_name: &str) -> Result<()> {
-    let dir_path = dir_path.as_ref();
+/// A JSON document being edited.
+pub struct JsonDocument {
+    content: String,
+}
 
-    if !dir_path.exists() || !dir_path.is_dir() {
-        return Ok(());
+impl JsonDocument {
+    pub fn parse(content: &str) -> Result<Self> {
+        serde_json::from_str::<serde_json::Value>(content)
+            .map_err(|e| eyre!("Invalid JSON: {}", e))?;
+        Ok(Self {
+            content: content.to_string(),
+        })
     }
 
-    for entry in fs::read_dir(dir_path)? {
-        let entry = entry?;
-        let path = entry.path();
+    pub fn into_string(self) -> String {
+        self.content
+    }
 
-        if path.is_dir() {
-            // Recursively search in subdirectories
-            update_package_json_in_dir(&path, new_name)?;
-        } else if path.file_name().and_then(|n| n.to_str()) == Some("package.json") {
-            // Found package.json, update it
-            update_package_json_name(&path, new_name)?;
+    /// Returns the members of the object at the top level, or under the
+    /// object-valued `path` from there.
+    pub fn members(&self, path: &[&str]) -> Vec<Member> {
+        let mut start = skip_whitespace(&self.content, 0);
+        for key in path {
+            let Some(member) = object_members(&self.content, start)
+                .into_iter()
+                .find(|member| member.key == *key)
+            else {
+                return Vec::new();
+            };
+            start = member.value.start;
         }
+        object_members(&self.content, start)
     }
 
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use tempfile::tempdir;
+    /// Returns the string value of `key` in the top-level object.
+    pub fn get_str(&self, key: &str) -> Option<String> {
+        let member = self.member(key)?;
+        serde_json::from_str(&self.content[member.value]).ok()
+    }
 
-    #[test]
-    fn test_update_package_json_name() -> Result<()> {
-        let temp_dir = tempdir()?;
-        let package_json_path = temp_dir.path().join("package.json");
-
-        let test_json = r#"
-        {
-            "name": "old-name",
-            "version": "1.0.0",
-            "description": "Test package"
+    /// Sets `key` in the top-level object to the string `value`, if present.
+    pub fn set_str(&mut self, key: &str, value: &str) -> bool {
+        let Some(member) = self.member(key) else {
+            return false;
+        };
+        let encoded = serde_json::Value::from(value).to_string();
+        if self.content[member.value.clone()] == encoded {
+            return false;
         }
-        "#;
+        self.content.replace_range(member.value, &encoded);
+        true
+    }
 
-        fs::write(&package_json_path, test_json)?;
+    /// Removes `key` from the top-level object, with its separating comma.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let members = self.members(&[]);
+        let Some(index) = members.iter().position(|member| member.key == key) else {
+            return false;
+        };
+        let range = match (index.checked_sub(1), members.get(index + 1)) {
+            // Up to the next key, so that member keeps its indentation
+            (_, Some(next)) => members[index].key_span.start..next.key_span.start,
+            // From the end of the previous value, dropping its comma
+            (Some(previous), None) => members[previous].value.end..members[index].value.end,
+            (None, None) => members[index].key_span.start..members[index].value.end,
+        };
+        self.content.replace_range(range, "");
+        true
+    }
 
-        update_package_json_name(&package_json_path, "new-project-name")?;
+    /// Renames the keys of the object under `path` mapped by `rename`.
+    pub fn rename_keys(&mut self, path: &[&str], rename: impl Fn(&str) -> Option<String>) -> bool {
+        let mut changed = false;
+        // Replace from the end so earlier ranges stay valid
+        for member in self.members(path).into_iter().rev() {
+            if let Some(new) = rename(&member.key) {
+                let encoded = serde_json::Value::from(new).to_string();
+                self.content.replace_range(member.key_span, &encoded);
+                changed = true;
+            }
+        }
+        changed
+    }
 
-        let updated_content = fs::read_to_string(&package_json_path)?;
-        let package_json: Value = serde_json::from_str(&updated_content)?;
+    fn member(&self, key: &str) -> Option<Member> {
+        self.members(&[])
+            .into_iter()
+            .find(|member| member.key == key)
+    }
+}
 
-        assert_eq!(package_json["name"], "new-project-name");
-        assert_eq!(package_json["version"], "1.0.0");
-        assert_eq!(package_json["description"], "Test package");
+/// Lists the members of the object starting at `start`, or nothing if the
+/// value there is not an object. The document must be valid JSON.
+fn object_members(content: &str, start: usize) -> Vec<Member> {
+    let bytes = content.as_bytes();
+    let mut members = Vec::new();
+    if bytes.get(start) != Some(&b'{') {
+        return members;
+    }
 
-        Ok(())
+    let mut pos = skip_whitespace(content, start + 1);
+    while bytes.get(pos) == Some(&b'"') {
+        let key_end = skip_string(bytes, pos);
+        let key = serde_json::from_str(&content[pos..key_end]).unwrap_or_default();
+        let colon = skip_whitespace(content, key_end);
+        let value_start = skip_whitespace(content, colon + 1);
+        let value_end = skip_value(bytes, value_start);
+        members.push(Member {
+            key,
+            key_span: pos..key_end,
+            value: value_start..value_end,
+        });
+
+        pos = skip_whitespace(content, value_end);
+        if bytes.get(pos) != Some(&b',') {
+            break;
+        }
+        pos = skip_whitespace(content, pos + 1);
     }
+    members
+}
 
-    #[test]
-    fn test_update_package_json_in_dir() -> Result<()> {
-        let temp_dir = tempdir()?;
-        let sub_dir = temp_dir.path().join("subdir");
-        fs::create_dir(&sub_dir)?;
-
-        let root_package = temp_dir.path().join("package.json");
-        let sub_package = sub_dir.join("package.json");
-
-        let test_json = r#"
-        {
-            "name": "old-name",
-            "version": "1.0.0"
+fn skip_whitespace(content: &str, pos: usize) -> usize {
+    pos + content[pos..].len() - content[pos..].trim_start().len()
+}
+
+/// Returns the position after the string starting at `pos`.
+fn skip_string(bytes: &[u8], pos: usize) -> usize {
+    let mut i = pos + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
         }
-        "#;
+    }
+    bytes.len()
+}
 
-        fs::write(&root_package, test_json)?;
-        fs::write(&sub_package, test_json)?;
+/// Returns the position after the value starting at `pos`.
+fn skip_value(bytes: &[u8], pos: usize) -> usize {
+    match bytes.get(pos) {
+        Some(b'"') => skip_string(bytes, pos),
+        Some(b'{' | b'[') => {
+            let mut depth = 0;
+            let mut i = pos;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'"' => {
+                        i = skip_string(bytes, i);
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return i + 1;
+                        }
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+            bytes.len()
+        }
+        _ => {
+            let mut i = pos;
+            while i < bytes.len() && !matches!(bytes[i], b',' | b'}' | b']') {
+                i += 1;
+            }
+            // Literals end before any whitespace preceding the delimiter
+            while i > pos && bytes[i - 1].is_ascii_whitespace() {
+                i -= 1;
+            }
+            i
+        }
+    }
+}
 
-        update_package_json_in_dir(temp_dir.path(), "updated-project")?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let root_content = fs::read_to_string(&root_package)?;
-        let sub_content = fs::read_to_string(&sub_package)?;
+    const PACKAGE: &str = "{\n  \"name\": \"old-name\",\n  \"version\": \"1.0.0\",\n  \"scripts\": { \"build\": \"tsc\" },\n  \"private\": true,\n  \"author\": \"Jane \\\"JD\\\" Doe\"\n}\n";
 
-        let root_json: Value = serde_json::from_str(&root_content)?;
-        let sub_json: Value = serde_json::from_str(&sub_content)?;
+    #[test]
+    fn test_set_str_preserves_formatting() -> Result<()> {
+        let mut document = JsonDocument::parse(PACKAGE)?;
+        assert_eq!(
+            document.get_str("author").as_deref(),
+            Some("Jane \"JD\" Doe")
+        );
+        assert!(document.set_str("name", "new-project-name"));
+        assert!(!document.set_str("name", "new-project-name"));
+        assert!(!document.set_str("missing", "value"));
+        assert_eq!(
+            document.into_string(),
+            PACKAGE.replace("old-name", "new-project-name")
+        );
+        Ok(())
+    }
 
-        assert_eq!(root_json["name"], "updated-project");
-        assert_eq!(sub_json["name"], "updated-project");
+    #[test]
+    fn test_remove() -> Result<()> {
+        let mut document = JsonDocument::parse(PACKAGE)?;
+        assert!(document.remove("version"));
+        assert!(document.remove("author"));
+        assert!(!document.remove("author"));
+        assert_eq!(
+            document.into_string(),
+            "{\n  \"name\": \"old-name\",\n  \"scripts\": { \"build\": \"tsc\" },\n  \"private\": true\n}\n"
+        );
+        Ok(())
+    }
 
+    #[test]
+    fn test_rename_keys() -> Result<()> {
+        let mut document = JsonDocument::parse(PACKAGE)?;
+        let renamed = document.rename_keys(&["scripts"], |key| {
+            (key == "build").then(|| "compile".to_string())
+        });
+        assert!(renamed);
+        assert!(document.members(&["name"]).is_empty());
+        assert!(document
+            .into_string()
+            .contains("\"scripts\": { \"compile\": \"tsc\" },"));
         Ok(())
     }
 }
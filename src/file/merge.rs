@@ -0,0 +1,452 @@
+use crate::manifest::Manifest;
+use crate::trash::{Trash, TrashEntry};
+use color_eyre::{eyre::eyre, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Suffix of the copy written next to an existing file by [`Strategy::KeepBoth`].
+pub const NEW_SUFFIX: &str = ".ogito-new";
+
+/// How a generated file that already exists in the destination is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Keep the existing file.
+    Skip,
+    /// Replace the existing file.
+    Overwrite,
+    /// Keep the existing file and write the new one next to it.
+    KeepBoth,
+    /// Ask for each file.
+    Prompt,
+}
+
+impl Strategy {
+    pub const NAMES: [&'static str; 4] = ["skip", "overwrite", "keep-both", "prompt"];
+
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "skip" => Ok(Self::Skip),
+            "overwrite" => Ok(Self::Overwrite),
+            "keep-both" => Ok(Self::KeepBoth),
+            "prompt" => Ok(Self::Prompt),
+            _ => Err(eyre!(
+                "Invalid merge strategy '{}': expected one of {}",
+                name,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// What merging does with a generated file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Created,
+    /// The existing file already has the same content.
+    Unchanged,
+    Skipped,
+    /// The existing file is replaced, and kept in the trash.
+    Overwritten,
+    /// The new file is written to the given path instead.
+    KeptBoth(PathBuf),
+}
+
+/// What merging a generated project will do, decided before anything is
+/// written.
+#[derive(Debug, Default)]
+pub struct MergePlan {
+    /// Directories to create, relative to the destination, parents first.
+    dirs: Vec<PathBuf>,
+    /// The outcome for every generated file, relative to the destination.
+    pub outcomes: Vec<(PathBuf, Outcome)>,
+}
+
+/// Decides how the generated files in `source` merge into the existing
+/// `destination`, resolving conflicts with `strategy`. For
+/// [`Strategy::Prompt`], `prompt` is called with the relative path, the new
+/// file and the existing file, and returns the strategy to apply to that file.
+/// Files only in `destination` are never touched. Nothing is written.
+pub fn plan_merge(
+    source: &Path,
+    destination: &Path,
+    strategy: Strategy,
+    prompt: &mut dyn FnMut(&Path, &Path, &Path) -> Result<Strategy>,
+) -> Result<MergePlan> {
+    let mut planner = Planner {
+        source,
+        destination,
+        strategy,
+        prompt,
+        reserved: HashSet::new(),
+        plan: MergePlan::default(),
+    };
+    planner.visit(source)?;
+    Ok(planner.plan)
+}
+
+struct Planner<'a> {
+    source: &'a Path,
+    destination: &'a Path,
+    strategy: Strategy,
+    prompt: &'a mut dyn FnMut(&Path, &Path, &Path) -> Result<Strategy>,
+    /// Paths the plan writes to, so kept copies never collide with them.
+    reserved: HashSet<PathBuf>,
+    plan: MergePlan,
+}
+
+impl Planner<'_> {
+    fn visit(&mut self, dir: &Path) -> Result<()> {
+        let mut entries = fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let path = entry.path();
+            let relative = path.strip_prefix(self.source)?.to_path_buf();
+            // The template's repository never replaces the destination's
+            if relative == Path::new(".git") {
+                continue;
+            }
+
+            let target = self.destination.join(&relative);
+            let existing = fs::symlink_metadata(&target).ok();
+            if entry.file_type()?.is_dir() {
+                match existing {
+                    Some(metadata) if metadata.is_dir() => {}
+                    Some(_) => {
+                        self.plan.outcomes.push((relative, Outcome::Skipped));
+                        continue;
+                    }
+                    None => self.plan.dirs.push(relative.clone()),
+                }
+                self.visit(&path)?;
+                continue;
+            }
+
+            let outcome = match existing {
+                None => Outcome::Created,
+                Some(metadata) if metadata.is_dir() => Outcome::Skipped,
+                Some(_) if same_content(&path, &target)? => Outcome::Unchanged,
+                Some(_) => {
+                    let resolved = match self.strategy {
+                        Strategy::Prompt => (self.prompt)(&relative, &path, &target)?,
+                        strategy => strategy,
+                    };
+                    match resolved {
+                        Strategy::Skip | Strategy::Prompt => Outcome::Skipped,
+                        Strategy::Overwrite => Outcome::Overwritten,
+                        Strategy::KeepBoth => Outcome::KeptBoth(self.free_name(&target)),
+                    }
+                }
+            };
+            if outcome == Outcome::Created {
+                self.reserved.insert(target);
+            }
+            self.plan.outcomes.push((relative, outcome));
+        }
+        Ok(())
+    }
+
+    /// Returns a path next to `target` for the new copy that is neither on
+    /// disk nor already planned.
+    fn free_name(&mut self, target: &Path) -> PathBuf {
+        let mut name = target.file_name().unwrap_or_default().to_os_string();
+        name.push(NEW_SUFFIX);
+        let mut candidate = target.with_file_name(&name);
+        let mut n = 1;
+        while fs::symlink_metadata(&candidate).is_ok() || self.reserved.contains(&candidate) {
+            let mut numbered = name.clone();
+            numbered.push(format!(".{n}"));
+            candidate = target.with_file_name(numbered);
+            n += 1;
+        }
+        self.reserved.insert(candidate.clone());
+        candidate
+    }
+}
+
+/// Whether `a` and `b` hold the same content, comparing symlinks by target.
+fn same_content(a: &Path, b: &Path) -> Result<bool> {
+    let (a_link, b_link) = (a.is_symlink(), b.is_symlink());
+    if a_link || b_link {
+        return Ok(a_link && b_link && fs::read_link(a)? == fs::read_link(b)?);
+    }
+    Ok(fs::read(a)? == fs::read(b)?)
+}
+
+/// Writes `plan` into `destination`. Overwritten files are moved to a trash
+/// entry for `operation`, which is returned. If anything fails, every change is
+/// undone before the error is returned.
+pub fn apply_merge(
+    source: &Path,
+    destination: &Path,
+    plan: &MergePlan,
+    trash: &Trash,
+    operation: &str,
+) -> Result<Option<TrashEntry>> {
+    let mut journal = Journal::default();
+    match write_plan(source, destination, plan, trash, operation, &mut journal) {
+        Ok(()) => Ok(journal.replaced),
+        Err(e) => {
+            journal.roll_back(trash)?;
+            Err(e)
+        }
+    }
+}
+
+/// The changes made so far by [`apply_merge`].
+#[derive(Default)]
+struct Journal {
+    /// Files and directories written, in order.
+    written: Vec<PathBuf>,
+    replaced: Option<TrashEntry>,
+}
+
+impl Journal {
+    fn roll_back(self, trash: &Trash) -> Result<()> {
+        for path in self.written.iter().rev() {
+            let result = match fs::symlink_metadata(path) {
+                Ok(metadata) if metadata.is_dir() => fs::remove_dir(path),
+                Ok(_) => fs::remove_file(path),
+                Err(_) => continue,
+            };
+            match result {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        if let Some(entry) = &self.replaced {
+            trash.restore(entry, &mut Manifest::default())?;
+        }
+        Ok(())
+    }
+}
+
+fn write_plan(
+    source: &Path,
+    destination: &Path,
+    plan: &MergePlan,
+    trash: &Trash,
+    operation: &str,
+    journal: &mut Journal,
+) -> Result<()> {
+    for dir in &plan.dirs {
+        let target = destination.join(dir);
+        fs::create_dir(&target)?;
+        journal.written.push(target);
+    }
+
+    for (relative, outcome) in &plan.outcomes {
+        let path = source.join(relative);
+        let target = destination.join(relative);
+        match outcome {
+            Outcome::Created => {
+                journal.written.push(target.clone());
+                copy_entry(&path, &target)?;
+            }
+            Outcome::Overwritten => {
+                let entry = match &mut journal.replaced {
+                    Some(entry) => entry,
+                    None => journal.replaced.insert(trash.create_entry(operation)?),
+                };
+                entry.store(&target)?;
+                journal.written.push(target.clone());
+                copy_entry(&path, &target)?;
+            }
+            Outcome::KeptBoth(kept) => {
+                journal.written.push(kept.clone());
+                copy_entry(&path, kept)?;
+            }
+            Outcome::Unchanged | Outcome::Skipped => {}
+        }
+    }
+    Ok(())
+}
+
+/// Copies the file at `from` to `to`, recreating symlinks rather than
+/// following them.
+fn copy_entry(from: &Path, to: &Path) -> Result<()> {
+    #[cfg(unix)]
+    if from.is_symlink() {
+        std::os::unix::fs::symlink(fs::read_link(from)?, to)?;
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn setup() -> Result<(tempfile::TempDir, tempfile::TempDir)> {
+        let source = tempdir()?;
+        let destination = tempdir()?;
+        fs::create_dir_all(source.path().join("src"))?;
+        fs::create_dir_all(source.path().join(".git"))?;
+        fs::write(source.path().join(".git/HEAD"), "ref: refs/heads/main\n")?;
+        fs::write(source.path().join("README.md"), "# new\n")?;
+        fs::write(source.path().join("LICENSE"), "MIT\n")?;
+        fs::write(source.path().join("src/main.rs"), "fn main() {}\n")?;
+
+        fs::create_dir_all(destination.path().join("src"))?;
+        fs::write(destination.path().join("README.md"), "# mine\n")?;
+        fs::write(destination.path().join("LICENSE"), "MIT\n")?;
+        fs::write(destination.path().join("notes.txt"), "keep me\n")?;
+        Ok((source, destination))
+    }
+
+    fn merge(
+        source: &Path,
+        destination: &Path,
+        trash: &Trash,
+        strategy: Strategy,
+        prompt: &mut dyn FnMut(&Path, &Path, &Path) -> Result<Strategy>,
+    ) -> Result<Vec<(PathBuf, Outcome)>> {
+        let plan = plan_merge(source, destination, strategy, prompt)?;
+        apply_merge(source, destination, &plan, trash, "new --merge")?;
+        Ok(plan.outcomes)
+    }
+
+    #[test]
+    fn test_merge_strategies() -> Result<()> {
+        for (strategy, readme, outcome) in [
+            (Strategy::Skip, "# mine\n", Outcome::Skipped),
+            (Strategy::Overwrite, "# new\n", Outcome::Overwritten),
+        ] {
+            let (source, destination) = setup()?;
+            let trash = Trash::at(source.path().join("trash"));
+            let outcomes = merge(
+                source.path(),
+                destination.path(),
+                &trash,
+                strategy,
+                &mut |_, _, _| unreachable!(),
+            )?;
+            assert_eq!(
+                outcomes,
+                vec![
+                    (PathBuf::from("LICENSE"), Outcome::Unchanged),
+                    (PathBuf::from("README.md"), outcome),
+                    (PathBuf::from("src/main.rs"), Outcome::Created),
+                ]
+            );
+            let dest = destination.path();
+            assert_eq!(fs::read_to_string(dest.join("README.md"))?, readme);
+            assert_eq!(fs::read_to_string(dest.join("notes.txt"))?, "keep me\n");
+            assert!(!dest.join(".git").exists());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_overwritten_files_are_trashed() -> Result<()> {
+        let (source, destination) = setup()?;
+        let trash = Trash::at(source.path().join("trash"));
+        let plan = plan_merge(
+            source.path(),
+            destination.path(),
+            Strategy::Overwrite,
+            &mut |_, _, _| unreachable!(),
+        )?;
+        let entry = apply_merge(source.path(), destination.path(), &plan, &trash, "merge")?
+            .expect("the overwritten README is kept");
+
+        trash.restore(&entry, &mut Manifest::default())?;
+        assert_eq!(
+            fs::read_to_string(destination.path().join("README.md"))?,
+            "# mine\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_merge_is_rolled_back() -> Result<()> {
+        let (source, destination) = setup()?;
+        let trash = Trash::at(source.path().join("trash"));
+        fs::create_dir_all(source.path().join("docs"))?;
+        fs::write(source.path().join("docs/guide.md"), "guide\n")?;
+        let plan = plan_merge(
+            source.path(),
+            destination.path(),
+            Strategy::Overwrite,
+            &mut |_, _, _| unreachable!(),
+        )?;
+
+        // The last file to write disappears, so the merge fails midway
+        fs::remove_file(source.path().join("src/main.rs"))?;
+        assert!(apply_merge(source.path(), destination.path(), &plan, &trash, "merge").is_err());
+
+        let dest = destination.path();
+        assert_eq!(fs::read_to_string(dest.join("README.md"))?, "# mine\n");
+        assert!(!dest.join("docs").exists());
+        assert!(!dest.join("src/main.rs").exists());
+        assert!(trash.entries()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_keep_both_and_prompt() -> Result<()> {
+        let (source, destination) = setup()?;
+        let trash = Trash::at(source.path().join("trash"));
+        let dest = destination.path();
+        // An earlier kept copy is never clobbered
+        fs::write(dest.join("README.md.ogito-new"), "# older\n")?;
+        let outcomes = merge(
+            source.path(),
+            dest,
+            &trash,
+            Strategy::KeepBoth,
+            &mut |_, _, _| unreachable!(),
+        )?;
+        let kept = dest.join("README.md.ogito-new.1");
+        assert_eq!(outcomes[1].1, Outcome::KeptBoth(kept.clone()));
+        assert_eq!(fs::read_to_string(&kept)?, "# new\n");
+        assert_eq!(
+            fs::read_to_string(dest.join("README.md.ogito-new"))?,
+            "# older\n"
+        );
+        assert_eq!(fs::read_to_string(dest.join("README.md"))?, "# mine\n");
+
+        let (source, destination) = setup()?;
+        let mut asked = Vec::new();
+        merge(
+            source.path(),
+            destination.path(),
+            &trash,
+            Strategy::Prompt,
+            &mut |relative, _, _| {
+                asked.push(relative.to_path_buf());
+                Ok(Strategy::Overwrite)
+            },
+        )?;
+        assert_eq!(asked, vec![PathBuf::from("README.md")]);
+        assert_eq!(
+            fs::read_to_string(destination.path().join("README.md"))?,
+            "# new\n"
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_merge_copies_symlinks() -> Result<()> {
+        let (source, destination) = setup()?;
+        let trash = Trash::at(source.path().join("trash"));
+        std::os::unix::fs::symlink("README.md", source.path().join("docs.md"))?;
+        merge(
+            source.path(),
+            destination.path(),
+            &trash,
+            Strategy::Skip,
+            &mut |_, _, _| unreachable!(),
+        )?;
+        assert_eq!(
+            fs::read_link(destination.path().join("docs.md"))?,
+            PathBuf::from("README.md")
+        );
+        Ok(())
+    }
+}
@@ -1,24 +1,37 @@
 pub mod cache;
 pub mod copy;
+pub mod exclude;
 pub mod json;
+pub mod merge;
 pub mod path;
+pub mod placeholder;
+pub mod project;
+pub mod staging;
 
+use crate::fetch::network::Network;
 use crate::file::cache::{CacheConfig, CacheMetadata};
+use crate::file::copy::IGNORE_FILE;
+use crate::file::exclude::{ExcludeOptions, ExcludeRules};
+use crate::user_config::UserConfig;
 use color_eyre::eyre::eyre;
 use color_eyre::Result;
 use flate2::read::GzDecoder;
-use std::fs::{create_dir_all, read_dir, remove_dir_all, remove_file, File};
-use std::path::Path;
-use std::{io::Write, path::PathBuf};
-use tar::Archive;
+use indicatif::HumanBytes;
+use std::fs::{create_dir_all, hard_link, read_dir, remove_dir_all, remove_file, File};
+use std::path::{Component, Path};
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+};
+use tar::{Archive, EntryType};
 
 pub async fn download_file(url: &str, cache_metadata: &CacheMetadata) -> Result<PathBuf> {
-    let cache = CacheConfig::new(cache_metadata);
+    let cache = CacheConfig::new(cache_metadata)?;
     std::fs::create_dir_all(&cache.cache_hash_path)?;
 
     let archive_path = cache.archive_path;
     if !archive_path.exists() {
-        let response = reqwest::get(url).await?;
+        let response = Network::from_settings()?.get(url).await?;
 
         if !response.status().is_success() {
             return Err(eyre!(
@@ -40,33 +53,273 @@ pub async fn download_file(url: &str, cache_metadata: &CacheMetadata) -> Result<
     Ok(archive_path)
 }
 
-pub fn extract_archive(temp_file_path: &PathBuf, dir: &str) -> Result<()> {
+/// Largest total size extracted from an archive when `archive.max_size_mb`
+/// is not set.
+pub const DEFAULT_MAX_ARCHIVE_SIZE_MB: u64 = 1024;
+/// Most entries extracted from an archive when `archive.max_entries` is not
+/// set.
+pub const DEFAULT_MAX_ARCHIVE_ENTRIES: u64 = 100_000;
+
+/// Bounds on what an archive may expand to, guarding against archive bombs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractLimits {
+    pub max_bytes: u64,
+    pub max_entries: u64,
+}
+
+impl Default for ExtractLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_ARCHIVE_SIZE_MB * 1024 * 1024,
+            max_entries: DEFAULT_MAX_ARCHIVE_ENTRIES,
+        }
+    }
+}
+
+impl ExtractLimits {
+    /// Reads the limits from `archive.max_size_mb` and `archive.max_entries`.
+    pub fn from_settings() -> Self {
+        let settings = UserConfig::load().unwrap_or_default();
+        let number = |key: &str| settings.resolve(key).and_then(|value| value.parse().ok());
+        let defaults = Self::default();
+        Self {
+            max_bytes: number("archive.max_size_mb")
+                .map_or(defaults.max_bytes, |mb: u64| mb.saturating_mul(1024 * 1024)),
+            max_entries: number("archive.max_entries").unwrap_or(defaults.max_entries),
+        }
+    }
+}
+
+/// Counts what has been read from an archive against its limits.
+struct Budget<'a> {
+    limits: &'a ExtractLimits,
+    entries: u64,
+    bytes: u64,
+}
+
+impl<'a> Budget<'a> {
+    fn new(limits: &'a ExtractLimits) -> Self {
+        Self {
+            limits,
+            entries: 0,
+            bytes: 0,
+        }
+    }
+
+    fn charge(&mut self, size: u64) -> Result<()> {
+        self.entries += 1;
+        self.bytes = self.bytes.saturating_add(size);
+        if self.entries > self.limits.max_entries {
+            return Err(eyre!(
+                "The archive has more than {} entries. Raise 'archive.max_entries' if it is trusted.",
+                self.limits.max_entries
+            ));
+        }
+        if self.bytes > self.limits.max_bytes {
+            return Err(eyre!(
+                "The archive expands to more than {}. Raise 'archive.max_size_mb' if it is trusted.",
+                HumanBytes(self.limits.max_bytes)
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Returns `path` without the archive's top-level directory, rejecting
+/// absolute paths and `..` components.
+fn strip_top_level(path: &Path) -> Result<PathBuf> {
+    let mut stripped = PathBuf::new();
+    for (index, component) in path.components().enumerate() {
+        match component {
+            Component::Normal(part) if index > 0 => stripped.push(part),
+            Component::Normal(_) | Component::CurDir => {}
+            _ => {
+                return Err(eyre!(
+                    "Refusing to extract '{}': archive paths must be relative and stay inside the template",
+                    path.display()
+                ))
+            }
+        }
+    }
+    Ok(stripped)
+}
+
+/// Resolves `path` relative to the canonical directory `base`, following the
+/// links already on disk, so links extracted earlier cannot hide an escape.
+fn resolve_on_disk(base: &Path, path: &Path) -> PathBuf {
+    let mut resolved = base.to_path_buf();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(part) => {
+                resolved.push(part);
+                if let Ok(real) = std::fs::canonicalize(&resolved) {
+                    resolved = real;
+                }
+            }
+            // Absolute paths replace what was resolved so far
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+/// Returns the first ancestor of `path` inside `dir` that is a symlink.
+fn symlinked_ancestor<'a>(dir: &Path, path: &'a Path) -> Option<&'a Path> {
+    path.ancestors()
+        .skip(1)
+        .find(|ancestor| !ancestor.as_os_str().is_empty() && dir.join(ancestor).is_symlink())
+}
+
+/// Returns the `.ogitoignore` shipped at the root of an archive, if any.
+fn read_archive_ignore_file(
+    temp_file_path: &PathBuf,
+    limits: &ExtractLimits,
+) -> Result<Option<String>> {
+    let mut archive = Archive::new(GzDecoder::new(File::open(temp_file_path)?));
+    let mut budget = Budget::new(limits);
+
+    for entry_result in archive.entries()? {
+        let mut entry = entry_result?;
+        budget.charge(entry.size())?;
+        let path = strip_top_level(&entry.path()?)?;
+        if path == Path::new(IGNORE_FILE) {
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            return Ok(Some(content));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Extracts a template archive into `dir`, dropping the top-level directory.
+/// With `exclude` set, files excluded by the template's rules are skipped.
+///
+/// Entries escaping `dir`, through their path or as links, are rejected, as
+/// are archives exceeding `limits`.
+pub fn extract_archive(
+    temp_file_path: &PathBuf,
+    dir: &str,
+    exclude: Option<&ExcludeOptions>,
+    limits: &ExtractLimits,
+) -> Result<()> {
+    let rules = match exclude {
+        Some(options) => Some(ExcludeRules::new(
+            read_archive_ignore_file(temp_file_path, limits)?.as_deref(),
+            options,
+        )?),
+        None => None,
+    };
+
     let tar_gz = File::open(temp_file_path)?;
 
     let tar = GzDecoder::new(tar_gz);
     let mut archive = Archive::new(tar);
+    let mut budget = Budget::new(limits);
+    let dir = Path::new(dir);
+    create_dir_all(dir)?;
+    let root = dir.canonicalize()?;
 
     for entry_result in archive.entries()? {
         let mut entry = entry_result?;
+        budget.charge(entry.size())?;
 
-        let path = entry.path()?.into_owned();
-
-        let mut components = path.components();
-        if components.next().is_none() {
+        let entry_type = entry.header().entry_type();
+        // Global pax headers, such as the commit id GitHub adds, carry no file
+        if matches!(entry_type, EntryType::XGlobalHeader | EntryType::XHeader) {
             continue;
         }
 
-        let new_path: PathBuf = components.collect();
+        let path = entry.path()?.into_owned();
+        let new_path = strip_top_level(&path)?;
         if new_path.as_os_str().is_empty() {
             continue;
         }
 
-        let target_path = Path::new(dir).join(new_path);
+        let is_dir = entry_type.is_dir();
+        if rules
+            .as_ref()
+            .is_some_and(|rules| rules.is_excluded(&new_path, is_dir))
+        {
+            continue;
+        }
+
+        // A symlinked directory would take the entry somewhere else than its path says
+        if let Some(ancestor) = symlinked_ancestor(dir, &new_path) {
+            return Err(eyre!(
+                "Refusing to extract '{}': it is inside the symlink '{}'",
+                path.display(),
+                ancestor.display()
+            ));
+        }
+
+        let target_path = dir.join(&new_path);
         if let Some(parent) = target_path.parent() {
             create_dir_all(parent)?;
         }
 
-        entry.unpack(&target_path)?;
+        match entry_type {
+            EntryType::Regular | EntryType::Continuous | EntryType::Directory => {
+                entry.unpack(&target_path)?;
+            }
+            EntryType::Symlink => {
+                let link = entry
+                    .link_name()?
+                    .ok_or_else(|| eyre!("Symlink '{}' has no target", path.display()))?;
+                let relative = new_path.parent().unwrap_or(Path::new("")).join(&link);
+                if !resolve_on_disk(&root, &relative).starts_with(&root) {
+                    return Err(eyre!(
+                        "Refusing to extract '{}': the symlink points outside the template ({})",
+                        path.display(),
+                        link.display()
+                    ));
+                }
+                entry.unpack(&target_path)?;
+            }
+            EntryType::Link => {
+                // Hard links name their source by its path in the archive
+                let link = entry
+                    .link_name()?
+                    .ok_or_else(|| eyre!("Hard link '{}' has no target", path.display()))?
+                    .into_owned();
+                let source = strip_top_level(&link)?;
+                if let Some(ancestor) = symlinked_ancestor(dir, &source) {
+                    return Err(eyre!(
+                        "Refusing to extract '{}': the hard link goes through the symlink '{}'",
+                        path.display(),
+                        ancestor.display()
+                    ));
+                }
+                if source.as_os_str().is_empty()
+                    || !resolve_on_disk(&root, &source).starts_with(&root)
+                {
+                    return Err(eyre!(
+                        "Refusing to extract '{}': the hard link points outside the template ({})",
+                        path.display(),
+                        link.display()
+                    ));
+                }
+                hard_link(dir.join(&source), &target_path).map_err(|e| {
+                    eyre!(
+                        "Failed to extract hard link '{}' to '{}': {}",
+                        path.display(),
+                        link.display(),
+                        e
+                    )
+                })?;
+            }
+            other => {
+                return Err(eyre!(
+                    "Refusing to extract '{}': unsupported entry type {:?}",
+                    path.display(),
+                    other
+                ))
+            }
+        }
     }
 
     Ok(())
@@ -88,3 +341,149 @@ pub fn clear_directory(path: &PathBuf) -> Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use tar::{Builder, Header};
+    use tempfile::{tempdir, TempDir};
+
+    /// An archive entry: its raw path, type, and content or link target.
+    type Entry<'a> = (&'a str, EntryType, &'a str);
+
+    /// Builds a `.tar.gz` from `entries`, writing paths as given so malformed
+    /// ones end up in the archive as an attacker would craft them.
+    fn archive(dir: &TempDir, entries: &[Entry]) -> Result<PathBuf> {
+        let path = dir.path().join("archive.tar.gz");
+        let mut builder = Builder::new(GzEncoder::new(File::create(&path)?, Compression::fast()));
+        for (name, entry_type, data) in entries {
+            let mut header = Header::new_gnu();
+            header.as_mut_bytes()[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_entry_type(*entry_type);
+            header.set_mode(0o644);
+            let content = match entry_type {
+                EntryType::Symlink | EntryType::Link => {
+                    header.set_link_name(data)?;
+                    ""
+                }
+                _ => data,
+            };
+            header.set_size(content.len() as u64);
+            header.set_cksum();
+            builder.append(&header, content.as_bytes())?;
+        }
+        builder.into_inner()?.finish()?;
+        Ok(path)
+    }
+
+    /// Extracts `entries` into a fresh `out` directory, returning the error
+    /// message if extraction fails.
+    fn extract(entries: &[Entry], limits: &ExtractLimits) -> Result<(TempDir, Option<String>)> {
+        let dir = tempdir()?;
+        let path = archive(&dir, entries)?;
+        let out = dir.path().join("out");
+        create_dir_all(&out)?;
+        let result = extract_archive(&path, out.to_str().unwrap(), None, limits);
+        Ok((dir, result.err().map(|e| e.to_string())))
+    }
+
+    #[test]
+    fn test_extract_archive() -> Result<()> {
+        let (dir, error) = extract(
+            &[
+                ("pax_global_header", EntryType::XGlobalHeader, ""),
+                ("repo-abc/", EntryType::Directory, ""),
+                ("repo-abc/README.md", EntryType::Regular, "# starter\n"),
+                ("repo-abc/docs/index.md", EntryType::Regular, "docs\n"),
+                ("repo-abc/docs/readme", EntryType::Symlink, "../README.md"),
+                ("repo-abc/copy.md", EntryType::Link, "repo-abc/README.md"),
+            ],
+            &ExtractLimits::default(),
+        )?;
+        assert_eq!(error, None);
+
+        let out = dir.path().join("out");
+        assert_eq!(std::fs::read_to_string(out.join("copy.md"))?, "# starter\n");
+        #[cfg(unix)]
+        assert_eq!(
+            std::fs::read_to_string(out.join("docs/readme"))?,
+            "# starter\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_archive_rejects_escaping_entries() -> Result<()> {
+        let malicious: [&[Entry]; 9] = [
+            &[("repo/../../evil.txt", EntryType::Regular, "pwned")],
+            &[("/tmp/evil.txt", EntryType::Regular, "pwned")],
+            &[("repo/link", EntryType::Symlink, "../../outside")],
+            &[("repo/link", EntryType::Symlink, "/etc/passwd")],
+            &[("repo/hard", EntryType::Link, "/etc/passwd")],
+            &[("repo/hard", EntryType::Link, "repo/../../secret")],
+            // Each link stays inside on its own, but the second is written
+            // through the first, which leads back to the destination's parent
+            &[
+                ("repo/a/b/up", EntryType::Symlink, "../.."),
+                ("repo/a/b/up/escape", EntryType::Symlink, "../.."),
+            ],
+            // The text stays inside, but `up` already leads to the root
+            &[
+                ("repo/a/b/up", EntryType::Symlink, "../.."),
+                ("repo/esc", EntryType::Symlink, "a/b/up/.."),
+                ("repo/h", EntryType::Link, "repo/esc/secret"),
+            ],
+            // A hard link source reached through an extracted symlink
+            &[
+                ("repo/a/b/up", EntryType::Symlink, "../.."),
+                ("repo/h", EntryType::Link, "repo/a/b/up/secret"),
+            ],
+        ];
+
+        for entries in malicious {
+            let (dir, error) = extract(entries, &ExtractLimits::default())?;
+            let error = error.expect("the archive should be rejected");
+            assert!(error.starts_with("Refusing to extract"), "{error}");
+
+            // Nothing was written beside the destination
+            let names: Vec<_> = read_dir(dir.path())?
+                .map(|entry| entry.map(|entry| entry.file_name()))
+                .collect::<std::io::Result<_>>()?;
+            assert_eq!(names.len(), 2, "{names:?}");
+            assert!(!dir.path().join("evil.txt").exists());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_archive_limits() -> Result<()> {
+        let entries: &[Entry] = &[
+            ("repo/a.txt", EntryType::Regular, "aaaa"),
+            ("repo/b.txt", EntryType::Regular, "bbbb"),
+            ("repo/c.txt", EntryType::Regular, "cccc"),
+        ];
+
+        let limits = ExtractLimits {
+            max_bytes: u64::MAX,
+            max_entries: 2,
+        };
+        let (_dir, error) = extract(entries, &limits)?;
+        assert!(error.is_some_and(|e| e.contains("archive.max_entries")));
+
+        let limits = ExtractLimits {
+            max_bytes: 10,
+            max_entries: u64::MAX,
+        };
+        let (_dir, error) = extract(entries, &limits)?;
+        assert!(error.is_some_and(|e| e.contains("archive.max_size_mb")));
+
+        let limits = ExtractLimits {
+            max_bytes: 12,
+            max_entries: 3,
+        };
+        let (_dir, error) = extract(entries, &limits)?;
+        assert_eq!(error, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+use color_eyre::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Placeholder replaced with the project name when generating a project.
+pub const PROJECT_NAME: &str = "{{project_name}}";
+
+/// Replaces whole-word occurrences of `name` with the project name placeholder
+/// in every text file under `dir`.
+pub fn insert_placeholder(dir: &Path, name: &str) -> Result<()> {
+    rewrite_text_files(dir, &|content| {
+        let found = occurrences(content, name);
+        if found.is_empty() {
+            return None;
+        }
+        let mut updated = String::with_capacity(content.len());
+        let mut last = 0;
+        for start in found {
+            updated.push_str(&content[last..start]);
+            updated.push_str(PROJECT_NAME);
+            last = start + name.len();
+        }
+        updated.push_str(&content[last..]);
+        Some(updated)
+    })
+}
+
+/// Lines of a file, numbered from 1.
+pub type Lines = Vec<(usize, String)>;
+
+/// Returns the lines of every text file under `dir` that
+/// [`insert_placeholder`] would change.
+pub fn find_occurrences(dir: &Path, name: &str) -> Result<Vec<(PathBuf, Lines)>> {
+    let mut found = Vec::new();
+    visit_text_files(dir, &mut |path, content| {
+        let lines: Vec<_> = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !occurrences(line, name).is_empty())
+            .map(|(i, line)| (i + 1, line.trim().to_string()))
+            .collect();
+        if !lines.is_empty() {
+            found.push((path.to_path_buf(), lines));
+        }
+        Ok(())
+    })?;
+    found.sort();
+    Ok(found)
+}
+
+/// Returns where `name` appears in `content` as a whole word. Hyphens count as
+/// part of a word, so `my-app` is not found in `my-app-utils`.
+fn occurrences(content: &str, name: &str) -> Vec<usize> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    if name.is_empty() {
+        return Vec::new();
+    }
+    content
+        .match_indices(name)
+        .filter(|(start, _)| {
+            let before = content[..*start].chars().next_back();
+            let after = content[start + name.len()..].chars().next();
+            !before.is_some_and(is_word) && !after.is_some_and(is_word)
+        })
+        .map(|(start, _)| start)
+        .collect()
+}
+
+/// Replaces the project name placeholder with `name` in every text file under `dir`.
+pub fn render_placeholders(dir: &Path, name: &str) -> Result<()> {
+    rewrite_text_files(dir, &|content| {
+        content
+            .contains(PROJECT_NAME)
+            .then(|| content.replace(PROJECT_NAME, name))
+    })
+}
+
+/// Returns the text files under `dir` that contain the project name placeholder.
+pub fn find_placeholders(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    visit_text_files(dir, &mut |path, content| {
+        if content.contains(PROJECT_NAME) {
+            found.push(path.to_path_buf());
+        }
+        Ok(())
+    })?;
+    Ok(found)
+}
+
+fn rewrite_text_files(dir: &Path, rewrite: &dyn Fn(&str) -> Option<String>) -> Result<()> {
+    visit_text_files(dir, &mut |path, content| {
+        if let Some(updated) = rewrite(content) {
+            fs::write(path, updated)?;
+        }
+        Ok(())
+    })
+}
+
+fn visit_text_files(dir: &Path, visit: &mut dyn FnMut(&Path, &str) -> Result<()>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            if entry.file_name() != ".git" {
+                visit_text_files(&path, visit)?;
+            }
+        } else if file_type.is_file() {
+            // Binary files are left untouched
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            visit(&path, &content)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_placeholder_roundtrip() -> Result<()> {
+        let dir = tempdir()?;
+        let readme = dir.path().join("README.md");
+        fs::write(&readme, "# my-app\nmy-app is not my-application")?;
+
+        insert_placeholder(dir.path(), "my-app")?;
+        assert_eq!(
+            fs::read_to_string(&readme)?,
+            "# {{project_name}}\n{{project_name}} is not my-application"
+        );
+
+        render_placeholders(dir.path(), "shop")?;
+        assert_eq!(
+            fs::read_to_string(&readme)?,
+            "# shop\nshop is not my-application"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_placeholder_skips_longer_names() -> Result<()> {
+        let dir = tempdir()?;
+        let readme = dir.path().join("README.md");
+        fs::write(
+            &readme,
+            "app\nUses my-app-utils and app_config.\nThe app, `app`.\n",
+        )?;
+
+        let found = find_occurrences(dir.path(), "app")?;
+        assert_eq!(
+            found,
+            vec![(
+                readme.clone(),
+                vec![(1, "app".to_string()), (3, "The app, `app`.".to_string())]
+            )]
+        );
+
+        insert_placeholder(dir.path(), "app")?;
+        assert_eq!(
+            fs::read_to_string(&readme)?,
+            "{{project_name}}\nUses my-app-utils and app_config.\nThe {{project_name}}, `{{project_name}}`.\n"
+        );
+        Ok(())
+    }
+}
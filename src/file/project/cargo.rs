@@ -0,0 +1,178 @@
+use super::{set_toml_str, Location, ProjectFile, Renaming};
+use color_eyre::{eyre::eyre, Result};
+use regex::Regex;
+use toml_edit::{DocumentMut, Item, Key, Table};
+
+const ECOSYSTEM: &str = "rust";
+
+const DEPENDENCY_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// `Cargo.toml`: the package, library and binary names.
+pub struct CargoToml;
+
+impl ProjectFile for CargoToml {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name == "Cargo.toml"
+    }
+
+    fn declared_name(&self, content: &str) -> Option<String> {
+        let document: DocumentMut = content.parse().ok()?;
+        document["package"]["name"].as_str().map(String::from)
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        location: &Location,
+    ) -> Result<Option<String>> {
+        let mut document: DocumentMut = content
+            .parse()
+            .map_err(|e| eyre!("Invalid Cargo.toml: {}", e))?;
+        let mut changed = false;
+
+        if let Some(package) = document.get_mut("package").and_then(Item::as_table_mut) {
+            if let Some(old) = package.get("name").and_then(Item::as_str).map(String::from) {
+                if let Some(new) = renaming.map_root(&old, location.root) {
+                    changed |= set_toml_str(&mut package["name"], &new);
+                    renaming.record(ECOSYSTEM, &old, &new);
+                }
+            }
+        }
+
+        if let Some(lib) = document.get_mut("lib").and_then(Item::as_table_mut) {
+            // Library names are Rust identifiers
+            changed |= rename_target(lib, |name| {
+                renaming.map(name).map(|new| new.replace('-', "_"))
+            });
+        }
+        if let Some(bins) = document
+            .get_mut("bin")
+            .and_then(Item::as_array_of_tables_mut)
+        {
+            for bin in bins.iter_mut() {
+                changed |= rename_target(bin, |name| renaming.map(name));
+            }
+        }
+
+        Ok(changed.then(|| document.to_string()))
+    }
+}
+
+/// `Cargo.toml`: dependencies on the renamed packages of the workspace.
+/// Other crates are left alone, even when they share the project's prefix.
+pub struct CargoDependencies;
+
+impl ProjectFile for CargoDependencies {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name == "Cargo.toml"
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        _location: &Location,
+    ) -> Result<Option<String>> {
+        let renamed = renaming.renamed(ECOSYSTEM);
+        if renamed.is_empty() {
+            return Ok(None);
+        }
+
+        let mut document: DocumentMut = content
+            .parse()
+            .map_err(|e| eyre!("Invalid Cargo.toml: {}", e))?;
+        let mut dependencies: Vec<&mut Table> = Vec::new();
+        for (key, item) in document.iter_mut() {
+            if DEPENDENCY_TABLES.contains(&key.get()) {
+                dependencies.extend(item.as_table_mut());
+            } else if key.get() == "workspace" {
+                dependencies.extend(item.get_mut("dependencies").and_then(Item::as_table_mut));
+            }
+        }
+        let mut changed = false;
+        for table in dependencies {
+            changed |= rename_keys(table, |name| {
+                renamed
+                    .iter()
+                    .find(|(old, _)| old == name)
+                    .map(|(_, new)| new.clone())
+            });
+        }
+        Ok(changed.then(|| document.to_string()))
+    }
+}
+
+/// Rust sources: paths to renamed crates, such as `use starter_core::run`, and
+/// `extern crate` items. Paths are only rewritten where they start, and not in
+/// files declaring a module of the same name, which the path then refers to.
+pub struct RustSource;
+
+impl ProjectFile for RustSource {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name.ends_with(".rs")
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        _location: &Location,
+    ) -> Result<Option<String>> {
+        let mut updated = content.to_string();
+        for (old, new) in renaming.renamed(ECOSYSTEM) {
+            let (old, new) = (old.replace('-', "_"), new.replace('-', "_"));
+            let old = regex::escape(&old);
+            let regex = |pattern: String| {
+                Regex::new(&pattern).map_err(|e| eyre!("Failed to create regex: {}", e))
+            };
+
+            let extern_crate = regex(format!(r"\b(extern\s+crate\s+){old}\b"))?;
+            updated = extern_crate
+                .replace_all(&updated, |caps: &regex::Captures| {
+                    format!("{}{new}", &caps[1])
+                })
+                .into_owned();
+
+            let module = regex(format!(r"(?m)^\s*(pub(\([^)]*\))?\s+)?mod\s+{old}\b"))?;
+            if module.is_match(&updated) {
+                continue;
+            }
+            // Not after `::`, so `crate::starter::` and `self::starter::` are kept
+            let path = regex(format!(r"(^|[^\w:]){old}::"))?;
+            updated = path
+                .replace_all(&updated, |caps: &regex::Captures| {
+                    format!("{}{new}::", &caps[1])
+                })
+                .into_owned();
+        }
+        Ok((updated != content).then_some(updated))
+    }
+}
+
+/// Renames a `[lib]` or `[[bin]]` target mapped by `rename`.
+fn rename_target(target: &mut Table, rename: impl Fn(&str) -> Option<String>) -> bool {
+    let new = target.get("name").and_then(Item::as_str).and_then(rename);
+    new.is_some_and(|new| set_toml_str(&mut target["name"], &new))
+}
+
+/// Renames the keys of `table` mapped by `rename`, keeping their order.
+fn rename_keys(table: &mut Table, rename: impl Fn(&str) -> Option<String>) -> bool {
+    let keys: Vec<String> = table.iter().map(|(key, _)| key.to_string()).collect();
+    if !keys.iter().any(|key| rename(key).is_some()) {
+        return false;
+    }
+
+    let entries: Vec<(Key, Item)> = keys
+        .iter()
+        .filter_map(|key| table.remove_entry(key))
+        .collect();
+    for (key, item) in entries {
+        let key = match rename(key.get()) {
+            Some(new) => Key::new(new).with_leaf_decor(key.leaf_decor().clone()),
+            None => key,
+        };
+        table.insert_formatted(&key, item);
+    }
+    true
+}
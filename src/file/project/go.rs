@@ -0,0 +1,104 @@
+use super::{Location, ProjectFile, Renaming};
+use color_eyre::Result;
+
+const ECOSYSTEM: &str = "go";
+
+/// `go.mod`: the module path, whose last element is the project name.
+pub struct GoMod;
+
+impl GoMod {
+    /// Returns the byte range of the module path.
+    fn module_range(content: &str) -> Option<(usize, usize)> {
+        let mut offset = 0;
+        for line in content.split_inclusive('\n') {
+            if let Some(rest) = line.strip_prefix("module") {
+                let path = rest.trim().trim_matches('"');
+                if !path.is_empty() {
+                    let start = offset + line.find(path)?;
+                    return Some((start, start + path.len()));
+                }
+            }
+            offset += line.len();
+        }
+        None
+    }
+
+    /// Returns the index of the path element naming the project, skipping a
+    /// major version suffix such as `/v2`.
+    fn name_element(elements: &[&str]) -> usize {
+        let last = elements.len() - 1;
+        let is_version = |element: &str| {
+            element
+                .strip_prefix('v')
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        };
+        if last > 0 && is_version(elements[last]) {
+            last - 1
+        } else {
+            last
+        }
+    }
+}
+
+impl ProjectFile for GoMod {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name == "go.mod"
+    }
+
+    fn declared_name(&self, content: &str) -> Option<String> {
+        let (start, end) = Self::module_range(content)?;
+        let elements: Vec<&str> = content[start..end].split('/').collect();
+        Some(elements[Self::name_element(&elements)].to_string())
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        location: &Location,
+    ) -> Result<Option<String>> {
+        let Some((start, end)) = Self::module_range(content) else {
+            return Ok(None);
+        };
+        let old = &content[start..end];
+        let mut elements: Vec<String> = old.split('/').map(String::from).collect();
+        let index = Self::name_element(&old.split('/').collect::<Vec<_>>());
+        let Some(name) = renaming.map_root(&elements[index], location.root) else {
+            return Ok(None);
+        };
+        elements[index] = name;
+        let new = elements.join("/");
+
+        renaming.record(ECOSYSTEM, old, &new);
+        Ok(Some(format!(
+            "{}{}{}",
+            &content[..start],
+            new,
+            &content[end..]
+        )))
+    }
+}
+
+/// Go sources: import paths below a renamed module.
+pub struct GoSource;
+
+impl ProjectFile for GoSource {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name.ends_with(".go")
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        _location: &Location,
+    ) -> Result<Option<String>> {
+        let mut updated = content.to_string();
+        for (old, new) in renaming.renamed(ECOSYSTEM) {
+            for suffix in ['"', '/'] {
+                updated = updated.replace(&format!("\"{old}{suffix}"), &format!("\"{new}{suffix}"));
+            }
+        }
+        Ok((updated != content).then_some(updated))
+    }
+}
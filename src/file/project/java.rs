@@ -0,0 +1,190 @@
+use super::{Location, ProjectFile, Renaming};
+use color_eyre::Result;
+use regex::Regex;
+
+const ECOSYSTEM: &str = "java";
+
+/// `pom.xml`: the project's own `artifactId`.
+pub struct Pom;
+
+impl Pom {
+    /// Returns the byte range and parent element of every `artifactId` value,
+    /// ignoring commented-out elements.
+    fn artifact_ids(content: &str) -> Result<Vec<(usize, usize, String)>> {
+        let comments: Vec<(usize, usize)> = Regex::new(r"(?s)<!--.*?-->")?
+            .find_iter(content)
+            .map(|m| (m.start(), m.end()))
+            .collect();
+        let in_comment = |pos: usize| comments.iter().any(|(s, e)| (*s..*e).contains(&pos));
+
+        let tag = Regex::new(r"<(/?)([A-Za-z_][\w.:-]*)[^>]*?(/?)>")?;
+        let mut stack: Vec<String> = Vec::new();
+        let mut ids = Vec::new();
+        for caps in tag.captures_iter(content) {
+            let whole = caps.get(0).unwrap();
+            if in_comment(whole.start()) {
+                continue;
+            }
+            let name = &caps[2];
+            if &caps[1] == "/" {
+                if let Some(pos) = stack.iter().rposition(|open| open == name) {
+                    stack.truncate(pos);
+                }
+            } else if &caps[3] != "/" {
+                if name == "artifactId" {
+                    let start = whole.end();
+                    if let Some(len) = content[start..].find("</artifactId>") {
+                        let parent = stack.last().cloned().unwrap_or_default();
+                        ids.push((start, start + len, parent));
+                    }
+                }
+                stack.push(name.to_string());
+            }
+        }
+        Ok(ids)
+    }
+}
+
+impl ProjectFile for Pom {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name == "pom.xml"
+    }
+
+    fn declared_name(&self, content: &str) -> Option<String> {
+        Self::artifact_ids(content)
+            .ok()?
+            .into_iter()
+            .find(|(_, _, parent)| parent == "project")
+            .map(|(start, end, _)| content[start..end].trim().to_string())
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        location: &Location,
+    ) -> Result<Option<String>> {
+        let Some((start, end, _)) = Self::artifact_ids(content)?
+            .into_iter()
+            .find(|(_, _, parent)| parent == "project")
+        else {
+            return Ok(None);
+        };
+        let old = content[start..end].trim();
+        let Some(new) = renaming.map_root(old, location.root) else {
+            return Ok(None);
+        };
+        renaming.record(ECOSYSTEM, old, &new);
+        let mut updated = content.to_string();
+        updated.replace_range(start..end, &new);
+        Ok(Some(updated))
+    }
+}
+
+/// `pom.xml`: parent and dependency `artifactId`s naming renamed modules of
+/// the project. External artifacts are left alone, even when they share the
+/// project's prefix.
+pub struct PomReferences;
+
+impl ProjectFile for PomReferences {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name == "pom.xml"
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        _location: &Location,
+    ) -> Result<Option<String>> {
+        let renamed = renaming.renamed(ECOSYSTEM);
+        let mut updated = content.to_string();
+        let mut changed = false;
+        // Replace from the end so earlier ranges stay valid
+        for (start, end, parent) in Pom::artifact_ids(content)?.into_iter().rev() {
+            if parent == "project" {
+                continue;
+            }
+            let old = content[start..end].trim();
+            if let Some((_, new)) = renamed.iter().find(|(name, _)| name == old) {
+                updated.replace_range(start..end, new);
+                changed = true;
+            }
+        }
+        Ok(changed.then_some(updated))
+    }
+}
+
+/// `settings.gradle(.kts)`: `rootProject.name`.
+pub struct GradleSettings;
+
+impl ProjectFile for GradleSettings {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name == "settings.gradle" || file_name == "settings.gradle.kts"
+    }
+
+    fn declared_name(&self, content: &str) -> Option<String> {
+        let re = Regex::new(r#"rootProject\.name\s*=\s*['"]([^'"]+)['"]"#).ok()?;
+        Some(re.captures(content)?[1].to_string())
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        location: &Location,
+    ) -> Result<Option<String>> {
+        let root_name = Regex::new(r#"(rootProject\.name\s*=\s*['"])([^'"]+)(['"])"#)?;
+        let updated = root_name
+            .replace(content, |caps: &regex::Captures| {
+                match renaming.map_root(&caps[2], location.root) {
+                    Some(name) => {
+                        renaming.record(ECOSYSTEM, &caps[2], &name);
+                        format!("{}{}{}", &caps[1], name, &caps[3])
+                    }
+                    None => caps[0].to_string(),
+                }
+            })
+            .into_owned();
+        Ok((updated != content).then_some(updated))
+    }
+}
+
+/// `settings.gradle(.kts)`: included subprojects named after renamed modules
+/// of the project.
+pub struct GradleIncludes;
+
+impl ProjectFile for GradleIncludes {
+    fn matches(&self, file_name: &str) -> bool {
+        GradleSettings.matches(file_name)
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        _location: &Location,
+    ) -> Result<Option<String>> {
+        let renamed = renaming.renamed(ECOSYSTEM);
+        let quoted = Regex::new(r#"(['"]:?)([^'":]+)(['"])"#)?;
+        let updated: String = content
+            .split_inclusive('\n')
+            .map(|line| {
+                if !line.trim_start().starts_with("include") {
+                    return line.to_string();
+                }
+                quoted
+                    .replace_all(line, |caps: &regex::Captures| {
+                        let name = renamed
+                            .iter()
+                            .find(|(old, _)| *old == caps[2])
+                            .map_or(&caps[2], |(_, new)| new.as_str());
+                        format!("{}{}{}", &caps[1], name, &caps[3])
+                    })
+                    .into_owned()
+            })
+            .collect();
+
+        Ok((updated != content).then_some(updated))
+    }
+}
@@ -0,0 +1,448 @@
+//! Renames a generated project in the manifests of its ecosystem.
+//!
+//! Each supported file kind implements [`ProjectFile`]. Manifests are rewritten
+//! first and record the names they change, then source files update the
+//! references to those names, so a renamed crate or Go module still builds.
+
+mod cargo;
+mod go;
+mod java;
+mod npm;
+mod php;
+mod python;
+
+use color_eyre::Result;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A file that declares or references the project name.
+pub trait ProjectFile: Sync {
+    /// Whether this handler applies to the file with the given name.
+    fn matches(&self, file_name: &str) -> bool;
+
+    /// Returns the project name declared by the file, if it declares one.
+    fn declared_name(&self, _content: &str) -> Option<String> {
+        None
+    }
+
+    /// Returns the rewritten content, or `None` if nothing changes.
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        location: &Location,
+    ) -> Result<Option<String>>;
+}
+
+/// Where a file being renamed sits in the project.
+pub struct Location<'a> {
+    pub path: &'a Path,
+    /// Whether the file is at the project root rather than in a subproject.
+    pub root: bool,
+}
+
+impl Location<'_> {
+    /// Returns the name of the directory containing the file.
+    pub fn dir_name(&self) -> Option<&str> {
+        self.path.parent()?.file_name()?.to_str()
+    }
+}
+
+/// Manifests, applied before [`SOURCES`].
+static MANIFESTS: &[&dyn ProjectFile] = &[
+    &npm::PackageJson,
+    &cargo::CargoToml,
+    &python::Pyproject,
+    &python::SetupCfg,
+    &go::GoMod,
+    &php::Composer,
+    &java::Pom,
+    &java::GradleSettings,
+];
+
+/// Source files referencing names renamed by [`MANIFESTS`].
+static SOURCES: &[&dyn ProjectFile] = &[
+    &npm::PackageJsonDependencies,
+    &cargo::CargoDependencies,
+    &cargo::RustSource,
+    &go::GoSource,
+    &java::PomReferences,
+    &java::GradleIncludes,
+];
+
+/// Directories whose contents never belong to the project itself.
+const SKIPPED_DIRS: &[&str] = &[
+    ".git",
+    "bower_components",
+    "node_modules",
+    "target",
+    "vendor",
+];
+
+/// The project name change being applied.
+pub struct Renaming {
+    /// Name declared by the manifests at the project root, if any.
+    pub old: Option<String>,
+    pub new: String,
+    /// Whether to also reset metadata inherited from the template, such as
+    /// its version and author.
+    pub reset_metadata: bool,
+    /// Names changed so far per ecosystem, such as crates or module paths.
+    renamed: BTreeMap<&'static str, Vec<(String, String)>>,
+}
+
+impl Renaming {
+    pub fn new(old: Option<String>, new: &str) -> Self {
+        Self {
+            old,
+            new: new.to_string(),
+            reset_metadata: false,
+            renamed: BTreeMap::new(),
+        }
+    }
+
+    /// Records that a manifest renamed `old` to `new`, for the sources of
+    /// `ecosystem` to follow.
+    pub fn record(&mut self, ecosystem: &'static str, old: &str, new: &str) {
+        self.renamed
+            .entry(ecosystem)
+            .or_default()
+            .push((old.to_string(), new.to_string()));
+    }
+
+    /// Returns the names recorded for `ecosystem`.
+    pub fn renamed(&self, ecosystem: &str) -> &[(String, String)] {
+        self.renamed.get(ecosystem).map_or(&[], Vec::as_slice)
+    }
+
+    /// Maps a name derived from the old project name, such as `old` or
+    /// `old-core`, to the new one. Unrelated names are left alone.
+    pub fn map(&self, name: &str) -> Option<String> {
+        let old = self.old.as_deref()?;
+        if name == old {
+            return Some(self.new.clone());
+        }
+        ['-', '_', '.'].into_iter().find_map(|separator| {
+            name.strip_prefix(old)
+                .and_then(|rest| rest.strip_prefix(separator))
+                .map(|rest| format!("{}{}{}", self.new, separator, rest))
+        })
+    }
+
+    /// Like [`Renaming::map`], but the root project is always renamed.
+    pub fn map_root(&self, name: &str, root: bool) -> Option<String> {
+        match self.map(name) {
+            Some(name) => Some(name),
+            None if root && name != self.new => Some(self.new.clone()),
+            None => None,
+        }
+    }
+}
+
+/// Replaces a TOML string value, keeping its comments and whitespace.
+fn set_toml_str(item: &mut toml_edit::Item, new: &str) -> bool {
+    let Some(value) = item.as_value_mut() else {
+        return false;
+    };
+    let decor = value.decor().clone();
+    // Keep literal strings literal when the new value allows it
+    let literal = match value {
+        toml_edit::Value::String(old) => old
+            .as_repr()
+            .and_then(|repr| repr.as_raw().as_str())
+            .is_some_and(|raw| raw.starts_with('\'')),
+        _ => false,
+    };
+    *value = match format!("'{new}'").parse() {
+        Ok(quoted) if literal && !new.contains('\'') => quoted,
+        _ => toml_edit::Value::from(new),
+    };
+    *value.decor_mut() = decor;
+    true
+}
+
+/// Renames the project in `dir` to `name`, returning the files changed.
+pub fn rename_project(dir: &Path, name: &str, reset_metadata: bool) -> Result<Vec<PathBuf>> {
+    let changes = plan(dir, name, reset_metadata)?;
+    for (path, content) in &changes {
+        fs::write(path, content)?;
+    }
+    Ok(changes.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Computes the new content of every file renaming the project would change.
+pub fn plan(dir: &Path, name: &str, reset_metadata: bool) -> Result<Vec<(PathBuf, String)>> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut files)?;
+
+    let mut renaming = Renaming::new(declared_name(dir, &files), name);
+    renaming.reset_metadata = reset_metadata;
+    let mut changes: BTreeMap<PathBuf, String> = BTreeMap::new();
+    for handlers in [MANIFESTS, SOURCES] {
+        for path in &files {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            for handler in handlers.iter().filter(|h| h.matches(&file_name)) {
+                let content = match changes.get(path) {
+                    Some(content) => content.clone(),
+                    // Binary files are left untouched
+                    None => match fs::read_to_string(path) {
+                        Ok(content) => content,
+                        Err(_) => continue,
+                    },
+                };
+                let location = Location {
+                    path,
+                    root: path.parent() == Some(dir),
+                };
+                if let Some(updated) = handler.rename(&content, &mut renaming, &location)? {
+                    if updated != content {
+                        changes.insert(path.clone(), updated);
+                    }
+                }
+            }
+        }
+    }
+    Ok(changes.into_iter().collect())
+}
+
+/// Returns the name declared by the first manifest found at the root of `dir`.
+fn declared_name(dir: &Path, files: &[PathBuf]) -> Option<String> {
+    MANIFESTS.iter().find_map(|handler| {
+        files
+            .iter()
+            .filter(|path| path.parent() == Some(dir))
+            .filter(|path| {
+                let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                handler.matches(&file_name)
+            })
+            .find_map(|path| handler.declared_name(&fs::read_to_string(path).ok()?))
+    })
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            let name = entry.file_name();
+            if !SKIPPED_DIRS.iter().any(|skipped| name == *skipped) {
+                collect_files(&entry.path(), files)?;
+            }
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_map() {
+        let renaming = Renaming::new(Some("starter".to_string()), "shop");
+        assert_eq!(renaming.map("starter"), Some("shop".to_string()));
+        assert_eq!(renaming.map("starter-core"), Some("shop-core".to_string()));
+        assert_eq!(renaming.map("starter_cli"), Some("shop_cli".to_string()));
+        assert_eq!(renaming.map("starters"), None);
+        assert_eq!(renaming.map("serde"), None);
+        assert_eq!(renaming.map_root("app", true), Some("shop".to_string()));
+        assert_eq!(renaming.map_root("app", false), None);
+
+        let unknown = Renaming::new(None, "shop");
+        assert_eq!(unknown.map_root("app", true), Some("shop".to_string()));
+        assert_eq!(unknown.map("app"), None);
+    }
+
+    #[test]
+    fn test_rename_rust_workspace() -> Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("core/src"))?;
+        fs::create_dir_all(root.join("src"))?;
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"starter\" # the binary\nversion = \"0.1.0\"\n\n[workspace]\nmembers = [\"core\"]\n\n[dependencies]\nserde = \"1\"\nstarter-core = { path = \"core\" }\nstarter_dyn_templates = \"0.2\"\nanyhow = \"1\"\n",
+        )?;
+        fs::write(
+            root.join("core/Cargo.toml"),
+            "[package]\nname = \"starter-core\"\nversion = \"0.1.0\"\n",
+        )?;
+        fs::write(
+            root.join("src/main.rs"),
+            "use starter_core::run;\nuse starter_dyn_templates::Template;\n\nfn main() {\n    starter_core::run();\n}\n",
+        )?;
+
+        let changed = rename_project(root, "shop", false)?;
+        assert_eq!(changed.len(), 3);
+
+        assert_eq!(
+            fs::read_to_string(root.join("Cargo.toml"))?,
+            "[package]\nname = \"shop\" # the binary\nversion = \"0.1.0\"\n\n[workspace]\nmembers = [\"core\"]\n\n[dependencies]\nserde = \"1\"\nshop-core = { path = \"core\" }\nstarter_dyn_templates = \"0.2\"\nanyhow = \"1\"\n"
+        );
+        assert!(fs::read_to_string(root.join("core/Cargo.toml"))?.contains("name = \"shop-core\""));
+        assert_eq!(
+            fs::read_to_string(root.join("src/main.rs"))?,
+            "use shop_core::run;\nuse starter_dyn_templates::Template;\n\nfn main() {\n    shop_core::run();\n}\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_rust_keeps_modules() -> Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("src"))?;
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"starter\"\nversion = \"0.1.0\"\n",
+        )?;
+        fs::write(
+            root.join("src/main.rs"),
+            "extern crate starter;\nuse starter::run;\n\nfn main() {\n    let starter = \"starter\";\n    starter::run(starter);\n    crate::starter::init();\n}\n",
+        )?;
+        let module = "pub mod starter;\n\nfn init() {\n    starter::init();\n}\n";
+        fs::write(root.join("src/lib.rs"), module)?;
+
+        rename_project(root, "shop", false)?;
+        assert_eq!(
+            fs::read_to_string(root.join("src/main.rs"))?,
+            "extern crate shop;\nuse shop::run;\n\nfn main() {\n    let starter = \"starter\";\n    shop::run(starter);\n    crate::starter::init();\n}\n"
+        );
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs"))?, module);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_go_module() -> Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("cmd"))?;
+        fs::write(
+            root.join("go.mod"),
+            "module github.com/acme/starter\n\ngo 1.22\n",
+        )?;
+        fs::write(
+            root.join("cmd/main.go"),
+            "package main\n\nimport (\n\t\"fmt\"\n\t\"github.com/acme/starter/internal/app\"\n)\n",
+        )?;
+
+        rename_project(root, "shop", false)?;
+        assert_eq!(
+            fs::read_to_string(root.join("go.mod"))?,
+            "module github.com/acme/shop\n\ngo 1.22\n"
+        );
+        assert!(fs::read_to_string(root.join("cmd/main.go"))?
+            .contains("\"github.com/acme/shop/internal/app\""));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_other_ecosystems() -> Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::write(
+            root.join("pyproject.toml"),
+            "[project]\nname = 'starter'  # keep me\nversion = \"0.1.0\"\n",
+        )?;
+        fs::write(
+            root.join("setup.cfg"),
+            "[metadata]\nname = starter\nversion = 0.1.0\n\n[options]\nname = other\n",
+        )?;
+        fs::write(
+            root.join("composer.json"),
+            "{\n    \"name\": \"acme/starter\",\n    \"require\": {\n        \"php\": \">=8.1\"\n    }\n}\n",
+        )?;
+        fs::write(
+            root.join("pom.xml"),
+            "<project>\n  <parent>\n    <artifactId>spring-boot-starter-parent</artifactId>\n  </parent>\n  <!-- <artifactId>ignored</artifactId> -->\n  <artifactId>starter</artifactId>\n  <dependencies>\n    <dependency>\n      <artifactId>starter-core</artifactId>\n    </dependency>\n    <dependency>\n      <artifactId>starter-security</artifactId>\n    </dependency>\n    <dependency>\n      <artifactId>junit</artifactId>\n    </dependency>\n  </dependencies>\n</project>\n",
+        )?;
+        fs::create_dir(root.join("core"))?;
+        fs::write(
+            root.join("core/pom.xml"),
+            "<project>\n  <parent>\n    <artifactId>starter</artifactId>\n  </parent>\n  <artifactId>starter-core</artifactId>\n</project>\n",
+        )?;
+        fs::write(
+            root.join("settings.gradle"),
+            "rootProject.name = 'starter'\ninclude 'starter-core', ':starter-api'\n",
+        )?;
+
+        rename_project(root, "shop", false)?;
+        assert_eq!(
+            fs::read_to_string(root.join("pyproject.toml"))?,
+            "[project]\nname = 'shop'  # keep me\nversion = \"0.1.0\"\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("setup.cfg"))?,
+            "[metadata]\nname = shop\nversion = 0.1.0\n\n[options]\nname = other\n"
+        );
+        assert!(fs::read_to_string(root.join("composer.json"))?
+            .starts_with("{\n    \"name\": \"acme/shop\",\n"));
+        let pom = fs::read_to_string(root.join("pom.xml"))?;
+        assert!(pom.contains("<artifactId>spring-boot-starter-parent</artifactId>"));
+        assert!(pom.contains("<!-- <artifactId>ignored</artifactId> -->"));
+        assert!(pom.contains("  <artifactId>shop</artifactId>\n"));
+        assert!(pom.contains("<artifactId>shop-core</artifactId>"));
+        assert!(pom.contains("<artifactId>starter-security</artifactId>"));
+        assert!(pom.contains("<artifactId>junit</artifactId>"));
+        assert_eq!(
+            fs::read_to_string(root.join("core/pom.xml"))?,
+            "<project>\n  <parent>\n    <artifactId>shop</artifactId>\n  </parent>\n  <artifactId>shop-core</artifactId>\n</project>\n"
+        );
+        // Only subprojects known to be modules of the project are renamed
+        assert_eq!(
+            fs::read_to_string(root.join("settings.gradle"))?,
+            "rootProject.name = 'shop'\ninclude 'shop-core', ':starter-api'\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_npm_workspace() -> Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        for sub in ["packages/ui", "packages/utils", "node_modules/left-pad"] {
+            fs::create_dir_all(root.join(sub))?;
+        }
+        fs::write(
+            root.join("package.json"),
+            "{\n  \"name\": \"starter\",\n  \"version\": \"2.3.0\",\n  \"author\": \"Template Author\",\n  \"workspaces\": [\"packages/*\"],\n  \"dependencies\": {\n    \"starter\": \"workspace:*\",\n    \"react\": \"^18.0.0\"\n  }\n}\n",
+        )?;
+        fs::write(
+            root.join("packages/ui/package.json"),
+            "{\n    \"name\": \"starter\",\n    \"version\": \"2.3.0\"\n}\n",
+        )?;
+        fs::write(
+            root.join("packages/utils/package.json"),
+            "{ \"name\": \"@starter/utils\", \"dependencies\": { \"lodash\": \"4\" } }",
+        )?;
+        let vendored = "{\"name\":\"left-pad\"}";
+        fs::write(root.join("node_modules/left-pad/package.json"), vendored)?;
+
+        rename_project(root, "shop", true)?;
+        assert_eq!(
+            fs::read_to_string(root.join("package.json"))?,
+            "{\n  \"name\": \"shop\",\n  \"version\": \"0.1.0\",\n  \"workspaces\": [\"packages/*\"],\n  \"dependencies\": {\n    \"@shop/ui\": \"workspace:*\",\n    \"react\": \"^18.0.0\"\n  }\n}\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("packages/ui/package.json"))?,
+            "{\n    \"name\": \"@shop/ui\",\n    \"version\": \"0.1.0\"\n}\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("packages/utils/package.json"))?,
+            "{ \"name\": \"@shop/utils\", \"dependencies\": { \"lodash\": \"4\" } }"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("node_modules/left-pad/package.json"))?,
+            vendored
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use super::{Location, ProjectFile, Renaming};
+use crate::file::json::JsonDocument;
+use color_eyre::{eyre::eyre, Result};
+
+const ECOSYSTEM: &str = "npm";
+
+const DEPENDENCY_FIELDS: &[&str] = &[
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+/// Splits a package name into its scope, without the `@`, and its package.
+fn split_name(name: &str) -> (Option<&str>, &str) {
+    match name.strip_prefix('@').and_then(|name| name.split_once('/')) {
+        Some((scope, package)) => (Some(scope), package),
+        None => (None, name),
+    }
+}
+
+fn join_name(scope: Option<&str>, package: &str) -> String {
+    match scope {
+        Some(scope) => format!("@{scope}/{package}"),
+        None => package.to_string(),
+    }
+}
+
+fn parse(content: &str, location: &Location) -> Result<JsonDocument> {
+    JsonDocument::parse(content).map_err(|e| eyre!("Invalid {}: {}", location.path.display(), e))
+}
+
+/// `package.json`: the package name, with workspace packages given names
+/// distinct from the root, and optionally its inherited metadata.
+pub struct PackageJson;
+
+impl PackageJson {
+    /// Returns the new name of the package called `name`, if it changes.
+    fn new_name(name: &str, renaming: &Renaming, location: &Location) -> Option<String> {
+        let (scope, package) = split_name(name);
+        let new_scope = scope.map(|scope| renaming.map(scope).unwrap_or_else(|| scope.to_string()));
+
+        if location.root {
+            let package = renaming
+                .map_root(package, true)
+                .unwrap_or_else(|| package.to_string());
+            return Some(join_name(new_scope.as_deref(), &package)).filter(|new| new != name);
+        }
+
+        // A workspace package sharing the root's name would clash with it
+        if renaming.old.as_deref() == Some(package) {
+            let dir_name = location.dir_name()?;
+            return Some(format!("@{}/{}", renaming.new, dir_name));
+        }
+
+        let package = renaming.map(package).unwrap_or_else(|| package.to_string());
+        Some(join_name(new_scope.as_deref(), &package)).filter(|new| new != name)
+    }
+}
+
+impl ProjectFile for PackageJson {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name == "package.json"
+    }
+
+    fn declared_name(&self, content: &str) -> Option<String> {
+        let name = JsonDocument::parse(content).ok()?.get_str("name")?;
+        Some(split_name(&name).1.to_string())
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        location: &Location,
+    ) -> Result<Option<String>> {
+        let mut document = parse(content, location)?;
+        let mut changed = false;
+
+        let old = document.get_str("name");
+        let new = old
+            .as_deref()
+            .and_then(|old| Self::new_name(old, renaming, location));
+        if let (Some(old), Some(new)) = (old, new) {
+            changed |= document.set_str("name", &new);
+            renaming.record(ECOSYSTEM, &old, &new);
+        }
+
+        if renaming.reset_metadata && (changed || location.root) {
+            changed |= document.set_str("version", "0.1.0");
+            changed |= document.set_str("description", "");
+            changed |= document.remove("repository");
+            changed |= document.remove("author");
+        }
+
+        Ok(changed.then(|| document.into_string()))
+    }
+}
+
+/// `package.json`: dependencies on renamed workspace packages.
+pub struct PackageJsonDependencies;
+
+impl ProjectFile for PackageJsonDependencies {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name == "package.json"
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        location: &Location,
+    ) -> Result<Option<String>> {
+        let renamed = renaming.renamed(ECOSYSTEM);
+        if renamed.is_empty() {
+            return Ok(None);
+        }
+
+        let mut document = parse(content, location)?;
+        let mut changed = false;
+        for field in DEPENDENCY_FIELDS {
+            changed |= document.rename_keys(&[field], |key| {
+                // A package sharing the root's old name is a workspace
+                // package, as the root cannot depend on itself
+                renamed
+                    .iter()
+                    .filter(|(old, _)| old == key)
+                    .min_by_key(|(_, new)| *new == renaming.new)
+                    .map(|(_, new)| new.clone())
+            });
+        }
+        Ok(changed.then(|| document.into_string()))
+    }
+}
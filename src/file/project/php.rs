@@ -0,0 +1,57 @@
+use super::{Location, ProjectFile, Renaming};
+use color_eyre::Result;
+use regex::Regex;
+
+/// `composer.json`: the package part of the top-level `vendor/package` name.
+pub struct Composer;
+
+impl Composer {
+    fn name(content: &str) -> Option<String> {
+        let json: serde_json::Value = serde_json::from_str(content).ok()?;
+        json.get("name")?.as_str().map(String::from)
+    }
+}
+
+impl ProjectFile for Composer {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name == "composer.json"
+    }
+
+    fn declared_name(&self, content: &str) -> Option<String> {
+        let name = Self::name(content)?;
+        Some(name.rsplit('/').next()?.to_string())
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        location: &Location,
+    ) -> Result<Option<String>> {
+        let Some(old) = Self::name(content) else {
+            return Ok(None);
+        };
+        let (vendor, package) = match old.rsplit_once('/') {
+            Some((vendor, package)) => (Some(vendor), package),
+            None => (None, old.as_str()),
+        };
+        let Some(package) = renaming.map_root(package, location.root) else {
+            return Ok(None);
+        };
+        let new = match vendor {
+            Some(vendor) => format!("{vendor}/{package}"),
+            None => package,
+        };
+
+        // Edit the text in place so the file keeps its formatting. The first
+        // `"name"` holding this value is the top-level one, which serializers
+        // write before nested objects.
+        let re = Regex::new(&format!(r#"("name"\s*:\s*")({})(")"#, regex::escape(&old)))?;
+        Ok(re.is_match(content).then(|| {
+            re.replace(content, |caps: &regex::Captures| {
+                format!("{}{}{}", &caps[1], new, &caps[3])
+            })
+            .into_owned()
+        }))
+    }
+}
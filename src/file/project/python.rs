@@ -0,0 +1,117 @@
+use super::{set_toml_str, Location, ProjectFile, Renaming};
+use color_eyre::{eyre::eyre, Result};
+use toml_edit::{DocumentMut, Item};
+
+/// Tables of `pyproject.toml` that declare the project name.
+const NAME_TABLES: &[&[&str]] = &[&["project"], &["tool", "poetry"]];
+
+/// `pyproject.toml`: `[project].name` and `[tool.poetry].name`.
+pub struct Pyproject;
+
+impl ProjectFile for Pyproject {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name == "pyproject.toml"
+    }
+
+    fn declared_name(&self, content: &str) -> Option<String> {
+        let document: DocumentMut = content.parse().ok()?;
+        NAME_TABLES.iter().find_map(|path| {
+            name_item(document.as_item(), path)?
+                .as_str()
+                .map(String::from)
+        })
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        location: &Location,
+    ) -> Result<Option<String>> {
+        let mut document: DocumentMut = content
+            .parse()
+            .map_err(|e| eyre!("Invalid pyproject.toml: {}", e))?;
+        let mut changed = false;
+
+        for path in NAME_TABLES {
+            let Some(item) = name_item_mut(document.as_item_mut(), path) else {
+                continue;
+            };
+            let Some(new) = item
+                .as_str()
+                .and_then(|old| renaming.map_root(old, location.root))
+            else {
+                continue;
+            };
+            changed |= set_toml_str(item, &new);
+        }
+
+        Ok(changed.then(|| document.to_string()))
+    }
+}
+
+fn name_item<'a>(item: &'a Item, path: &[&str]) -> Option<&'a Item> {
+    path.iter()
+        .try_fold(item, |item, key| item.get(key))?
+        .get("name")
+}
+
+fn name_item_mut<'a>(item: &'a mut Item, path: &[&str]) -> Option<&'a mut Item> {
+    path.iter()
+        .try_fold(item, |item, key| item.as_table_like_mut()?.get_mut(key))?
+        .as_table_like_mut()?
+        .get_mut("name")
+}
+
+/// `setup.cfg`: the `name` option of the `[metadata]` section.
+pub struct SetupCfg;
+
+impl SetupCfg {
+    /// Returns the byte range of the value of `[metadata] name`.
+    fn name_range(content: &str) -> Option<(usize, usize)> {
+        let mut in_metadata = false;
+        let mut offset = 0;
+        for line in content.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                in_metadata = trimmed == "[metadata]";
+            } else if in_metadata {
+                if let Some((key, value)) = line.split_once('=') {
+                    if key.trim() == "name" {
+                        let value_start = offset + key.len() + 1;
+                        let leading = value.len() - value.trim_start().len();
+                        let start = value_start + leading;
+                        return Some((start, start + value.trim().len()));
+                    }
+                }
+            }
+            offset += line.len();
+        }
+        None
+    }
+}
+
+impl ProjectFile for SetupCfg {
+    fn matches(&self, file_name: &str) -> bool {
+        file_name == "setup.cfg"
+    }
+
+    fn declared_name(&self, content: &str) -> Option<String> {
+        let (start, end) = Self::name_range(content)?;
+        Some(content[start..end].to_string())
+    }
+
+    fn rename(
+        &self,
+        content: &str,
+        renaming: &mut Renaming,
+        location: &Location,
+    ) -> Result<Option<String>> {
+        let Some((start, end)) = Self::name_range(content) else {
+            return Ok(None);
+        };
+        Ok(renaming
+            .map_root(&content[start..end], location.root)
+            .map(|new| format!("{}{}{}", &content[..start], new, &content[end..])))
+    }
+}
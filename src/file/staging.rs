@@ -0,0 +1,172 @@
+//! Generates projects in a scratch directory next to their destination, so a
+//! failed or interrupted run never leaves a half-written project behind.
+
+use crate::manifest::Manifest;
+use crate::trash::{self, Trash, TrashEntry};
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use tempfile::TempDir;
+
+/// Staging directories of runs in progress, removed if the process is
+/// interrupted.
+static PENDING: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+fn pending() -> MutexGuard<'static, Vec<PathBuf>> {
+    PENDING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Removes the staging directories of runs in progress on Ctrl-C, leaving
+/// every destination as it was.
+pub fn handle_interrupts() -> Result<()> {
+    ctrlc::set_handler(|| {
+        // Waits for a commit in progress, so the destination is never half-replaced
+        let pending = pending();
+        for dir in pending.iter() {
+            let _ = fs::remove_dir_all(dir);
+        }
+        let _ = console::Term::stderr().show_cursor();
+        if !pending.is_empty() {
+            eprintln!(
+                "\n{} Interrupted, the destination was left unchanged",
+                style("🛑").red()
+            );
+        }
+        std::process::exit(130);
+    })
+    .map_err(|e| eyre!("Failed to set the Ctrl-C handler: {}", e))
+}
+
+/// Runs `f` with Ctrl-C held back until it returns, for changes that must not
+/// be left half-done.
+pub fn uninterruptible<T>(f: impl FnOnce() -> T) -> T {
+    let _guard = pending();
+    f()
+}
+
+/// A project being generated for `destination`.
+pub struct Staging {
+    dir: TempDir,
+    path: PathBuf,
+    destination: PathBuf,
+}
+
+impl Staging {
+    /// Creates a staging directory beside `destination`, on the same file
+    /// system so the project can be moved into place in one step.
+    pub fn new(destination: &Path) -> Result<Self> {
+        let destination = std::path::absolute(destination)?;
+        let (Some(parent), Some(name)) = (destination.parent(), destination.file_name()) else {
+            return Err(eyre!("Invalid destination: {}", destination.display()));
+        };
+        fs::create_dir_all(parent)?;
+        let dir = tempfile::Builder::new()
+            .prefix(".ogito-staging-")
+            .tempdir_in(parent)?;
+        let path = dir.path().join(name);
+        pending().push(dir.path().to_path_buf());
+        Ok(Self {
+            dir,
+            path,
+            destination,
+        })
+    }
+
+    /// Returns where to generate the project. It is named like the
+    /// destination and does not exist yet.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Moves the generated project to the destination. Anything already there
+    /// is moved to the trash under `operation`, and put back if the project
+    /// cannot be moved into place.
+    pub fn commit(self, operation: &str) -> Result<Option<TrashEntry>> {
+        uninterruptible(|| self.replace_destination(operation))
+    }
+
+    fn replace_destination(&self, operation: &str) -> Result<Option<TrashEntry>> {
+        if !self.path.exists() {
+            return Err(eyre!(
+                "Nothing was generated for {}",
+                self.destination.display()
+            ));
+        }
+
+        let replaced = if !self.destination.exists() {
+            None
+        } else if fs::read_dir(&self.destination)?.next().is_none() {
+            fs::remove_dir(&self.destination)?;
+            None
+        } else {
+            Some(trash::discard(operation, &self.destination)?)
+        };
+
+        if let Err(e) = fs::rename(&self.path, &self.destination) {
+            if let Some(entry) = &replaced {
+                Trash::open()?.restore(entry, &mut Manifest::default())?;
+            }
+            return Err(eyre!(
+                "Failed to move the project into {}: {}",
+                self.destination.display(),
+                e
+            ));
+        }
+        Ok(replaced)
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        pending().retain(|dir| dir != self.dir.path());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_failed_run_leaves_destination_unchanged() -> Result<()> {
+        let dir = tempdir()?;
+        let destination = dir.path().join("app");
+        fs::create_dir_all(&destination)?;
+        fs::write(destination.join("notes.txt"), "keep me\n")?;
+
+        {
+            let staging = Staging::new(&destination)?;
+            fs::create_dir_all(staging.path())?;
+            fs::write(staging.path().join("README.md"), "# app\n")?;
+            // Dropped without committing, as when generation fails
+        }
+
+        let names: Vec<_> = fs::read_dir(dir.path())?
+            .map(|entry| entry.map(|entry| entry.file_name()))
+            .collect::<std::io::Result<_>>()?;
+        assert_eq!(names, vec!["app"]);
+        assert_eq!(
+            fs::read_to_string(destination.join("notes.txt"))?,
+            "keep me\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_into_empty_destination() -> Result<()> {
+        let dir = tempdir()?;
+        let destination = dir.path().join("app");
+        fs::create_dir_all(&destination)?;
+
+        let staging = Staging::new(&destination)?;
+        fs::create_dir_all(staging.path().join("src"))?;
+        fs::write(staging.path().join("src/main.rs"), "fn main() {}\n")?;
+        assert!(staging.commit("new app")?.is_none());
+
+        assert!(destination.join("src/main.rs").exists());
+        assert_eq!(fs::read_dir(dir.path())?.count(), 1);
+        Ok(())
+    }
+}
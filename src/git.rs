@@ -1,6 +1,9 @@
+use crate::fetch::network::Network;
 use color_eyre::{eyre::eyre, Result};
+use git2::{IndexAddOption, Oid, Repository, RepositoryInitOptions, Signature};
 use std::{
     io::Error,
+    path::Path,
     process::{Command, Output},
 };
 
@@ -22,11 +25,19 @@ impl<'a> Git<'a> {
         self
     }
 
-    pub fn ls_remote(&self) -> Result<Output, Error> {
-        Command::new(self.cmd)
-            .arg("ls-remote")
-            .args(&self.args)
-            .output()
+    /// Runs `git ls-remote` with the proxy and token for the repository,
+    /// which is the last argument.
+    pub fn ls_remote(&self, network: &Network) -> Result<Output, Error> {
+        let mut command = Command::new(self.cmd);
+        command.arg("ls-remote").args(&self.args);
+        if let Some(url) = self.args.last() {
+            network.configure_git(&mut command, url);
+        }
+        command.output()
+    }
+
+    pub fn version(&self) -> Result<Output, Error> {
+        Command::new(self.cmd).arg("--version").output()
     }
 }
 
@@ -38,7 +49,8 @@ pub struct RemoteRef {
 
 pub fn get_remote_refs(url: &str) -> Result<Vec<RemoteRef>> {
     let mut git = Git::new();
-    let output = git.args(vec![url]).ls_remote()?;
+    let network = Network::from_settings()?;
+    let output = git.args(vec![url]).ls_remote(&network)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -69,9 +81,40 @@ pub fn get_remote_refs(url: &str) -> Result<Vec<RemoteRef>> {
     Ok(refs)
 }
 
+/// Initializes a repository in `dir` on `branch` and commits every file not
+/// ignored by the project's `.gitignore`. Without an explicit author, the
+/// commit uses the identity from the user's git configuration.
+pub fn init_repository(
+    dir: &Path,
+    branch: &str,
+    message: &str,
+    author: Option<(&str, &str)>,
+) -> Result<Oid> {
+    let repo = Repository::init_opts(dir, RepositoryInitOptions::new().initial_head(branch))
+        .map_err(|e| eyre!("Failed to initialize a git repository: {}", e))?;
+
+    let mut index = repo.index()?;
+    index.add_all(["*"], IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+
+    let signature = match author {
+        Some((name, email)) => Signature::now(name, email)?,
+        None => repo.signature().map_err(|_| {
+            eyre!(
+                "No git author configured. Set one with 'ogito config set author.name <NAME>' and 'ogito config set author.email <EMAIL>'"
+            )
+        })?,
+    };
+    let oid = repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &[])?;
+    Ok(oid)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Git;
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
 
     #[test]
     fn test_git_args() {
@@ -83,4 +126,33 @@ mod tests {
             vec!["https://github.com/owner/repo.git", "/tmp/repo"]
         );
     }
+
+    #[test]
+    fn test_init_repository() -> Result<()> {
+        let dir = tempdir()?;
+        fs::create_dir(dir.path().join("src"))?;
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}\n")?;
+        fs::write(dir.path().join(".gitignore"), "/target\n")?;
+        fs::create_dir(dir.path().join("target"))?;
+        fs::write(dir.path().join("target/app"), "binary")?;
+
+        let oid = init_repository(
+            dir.path(),
+            "trunk",
+            "Initial commit",
+            Some(("Jane Doe", "jane@example.com")),
+        )?;
+
+        let repo = Repository::open(dir.path())?;
+        let head = repo.head()?;
+        assert_eq!(head.shorthand(), Some("trunk"));
+        let commit = repo.find_commit(oid)?;
+        assert_eq!(commit.message(), Some("Initial commit"));
+        assert_eq!(commit.author().email(), Some("jane@example.com"));
+        let tree = commit.tree()?;
+        assert!(tree.get_path(Path::new("src/main.rs")).is_ok());
+        assert!(tree.get_path(Path::new(".gitignore")).is_ok());
+        assert!(tree.get_path(Path::new("target/app")).is_err());
+        Ok(())
+    }
 }
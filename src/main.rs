@@ -8,6 +8,9 @@ mod manifest;
 mod mode;
 mod progress;
 mod regex;
+mod registry;
+mod trash;
+mod user_config;
 
 use color_eyre::Result;
 
@@ -21,6 +24,7 @@ async fn main() {
 
 async fn run() -> Result<()> {
     color_eyre::install()?;
+    file::staging::handle_interrupts()?;
     let matches = cli::build().get_matches();
     cli::dispatch(matches).await?;
     Ok(())
@@ -1,14 +1,37 @@
-use color_eyre::{eyre::Ok, Result};
-use serde::{Deserialize, Serialize};
+use crate::registry::Registry;
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::{
     collections::BTreeMap,
-    fs,
+    fs::{self, File, OpenOptions, TryLockError},
+    io::Write,
     path::{Path, PathBuf},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Version of the manifest format written by this build of ogito.
+///
+/// Bump it whenever the format changes and add the matching step to [`migrate`].
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Manifest {
+    /// Files written before versioning was introduced have no key and are version 0.
+    #[serde(default)]
+    pub schema_version: u32,
     pub templates: BTreeMap<String, Template>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub registries: BTreeMap<String, Registry>,
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            templates: BTreeMap::new(),
+            registries: BTreeMap::new(),
+        }
+    }
 }
 
 impl Manifest {
@@ -26,7 +49,7 @@ impl Manifest {
         if let Some((actual_name, _)) = self
             .templates
             .iter()
-            .find(|(_, template)| template.alias.as_ref() == Some(name))
+            .find(|(_, template)| template.aliases.contains(name))
             .map(|(k, v)| (k.clone(), v.clone()))
         {
             return self.templates.remove(&actual_name);
@@ -42,7 +65,7 @@ impl Manifest {
             .or_else(|| {
                 self.templates
                     .iter()
-                    .find(|(_, template)| template.alias.as_ref() == Some(name))
+                    .find(|(_, template)| template.aliases.contains(name))
                     .map(|(name, _)| name)
             })
     }
@@ -50,26 +73,103 @@ impl Manifest {
     pub fn clear(&mut self) {
         self.templates.clear();
     }
+
+    /// Returns an error if storing `template` under `name` would make a name
+    /// or alias ambiguous. The entry currently stored under `name` is ignored.
+    pub fn check_conflicts(&self, name: &str, template: &Template) -> Result<()> {
+        if template.aliases.iter().any(|alias| alias == name) {
+            return Err(eyre!("Alias '{}' is the name of the template itself", name));
+        }
+        for (other_name, other) in &self.templates {
+            if other_name == name {
+                continue;
+            }
+            if other.aliases.iter().any(|alias| alias == name) {
+                return Err(eyre!(
+                    "Name '{}' is already an alias of template '{}'",
+                    name,
+                    other_name
+                ));
+            }
+            for alias in &template.aliases {
+                if alias == other_name {
+                    return Err(eyre!(
+                        "Alias '{}' would shadow template '{}'",
+                        alias,
+                        other_name
+                    ));
+                }
+                if other.aliases.contains(alias) {
+                    return Err(eyre!(
+                        "Alias '{}' is already used by template '{}'",
+                        alias,
+                        other_name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the entry `old` to `new`, keeping its metadata.
+    pub fn rename_template(&mut self, old: &str, new: &str) -> Result<()> {
+        if self.templates.contains_key(new) {
+            return Err(eyre!("Template '{}' already exists", new));
+        }
+        let template = self
+            .templates
+            .remove(old)
+            .ok_or_else(|| eyre!("Template '{}' not found", old))?;
+        if let Err(e) = self.check_conflicts(new, &template) {
+            self.templates.insert(old.to_string(), template);
+            return Err(e);
+        }
+        self.templates.insert(new.to_string(), template);
+        Ok(())
+    }
+
+    pub fn add_registry(&mut self, name: String, registry: Registry) {
+        self.registries.insert(name, registry);
+    }
+
+    pub fn remove_registry(&mut self, name: &str) -> Option<Registry> {
+        self.registries.remove(name)
+    }
 }
 
+/// A manifest loaded for modification.
+///
+/// An exclusive advisory lock on `<manifest>.lock` is held until the value is
+/// dropped, so concurrent ogito processes apply their changes one at a time.
 pub struct ManifestFile {
     pub path: PathBuf,
     pub content: Manifest,
+    _lock: File,
 }
 
 impl ManifestFile {
     pub fn load(path: &Path) -> Result<Self> {
-        if !path.exists() {
-            let content = Manifest::default();
-            let toml_content = toml::to_string_pretty(&content)?;
-            fs::write(path, toml_content)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
         }
+        let lock = acquire_lock(path)?;
+
+        let content = if path.exists() {
+            let (content, version) = Manifest::read_versioned(path)?;
+            if version < SCHEMA_VERSION {
+                upgrade_file(path, version, &content)?;
+            }
+            content
+        } else {
+            let content = Manifest::default();
+            write_atomic(path, &toml::to_string_pretty(&content)?)?;
+            content
+        };
 
-        let file_content = fs::read_to_string(path)?;
-        let content: Manifest = toml::from_str(&file_content)?;
         Ok(Self {
             path: path.to_path_buf(),
             content,
+            _lock: lock,
         })
     }
 
@@ -81,18 +181,309 @@ impl ManifestFile {
         self.content.remove_template(name)
     }
 
+    /// Writes the manifest atomically, keeping the previous version as a backup.
     pub fn save(&self) -> Result<()> {
         let toml_content = toml::to_string_pretty(&self.content)?;
-        fs::write(&self.path, toml_content)?;
+        if self.path.exists() {
+            fs::copy(&self.path, backup_path(&self.path))?;
+        }
+        write_atomic(&self.path, &toml_content)
+    }
+}
+
+impl Manifest {
+    /// Reads a manifest without locking it. Saves replace the file atomically,
+    /// so readers always see a complete version.
+    /// Older schema versions are upgraded in memory only.
+    pub fn read(path: &Path) -> Result<Self> {
+        Ok(Self::read_versioned(path)?.0)
+    }
+
+    /// Parses TOML manifest content, such as a registry index, upgrading it
+    /// from older schema versions.
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let mut table: toml::Table = content.parse()?;
+        upgrade(&mut table)?;
+        Ok(toml::Value::Table(table).try_into()?)
+    }
+
+    /// Returns the manifest at `path` and the schema version it was stored with.
+    fn read_versioned(path: &Path) -> Result<(Self, u32)> {
+        let content = fs::read_to_string(path)?;
+        let mut table: toml::Table = content
+            .parse()
+            .map_err(|e| corrupt_manifest_error(path, &e))?;
+        let version =
+            upgrade(&mut table).map_err(|e| eyre!("Cannot read {}: {}", path.display(), e))?;
+        let manifest = toml::Value::Table(table)
+            .try_into()
+            .map_err(|e| corrupt_manifest_error(path, &e))?;
+        Ok((manifest, version))
+    }
+}
+
+/// Checks a stored `schema_version` value, failing for versions newer than
+/// this build understands.
+pub fn check_schema_version(value: Option<i64>) -> Result<u32> {
+    let version = match value {
+        None => 0,
+        Some(value) => {
+            u32::try_from(value).map_err(|_| eyre!("Invalid schema_version {}", value))?
+        }
+    };
+    if version > SCHEMA_VERSION {
+        return Err(eyre!(
+            "The manifest uses schema version {}, but this ogito only supports up to version {}. Please upgrade ogito.",
+            version,
+            SCHEMA_VERSION
+        ));
+    }
+    Ok(version)
+}
+
+/// Brings a parsed manifest up to [`SCHEMA_VERSION`], returning its original version.
+fn upgrade(table: &mut toml::Table) -> Result<u32> {
+    let value = match table.get("schema_version") {
+        Some(value) => Some(
+            value
+                .as_integer()
+                .ok_or_else(|| eyre!("schema_version must be an integer"))?,
+        ),
+        None => None,
+    };
+    let version = check_schema_version(value)?;
+    for from in version..SCHEMA_VERSION {
+        migrate(table, from);
+    }
+    table.insert(
+        "schema_version".to_string(),
+        toml::Value::Integer(SCHEMA_VERSION.into()),
+    );
+    Ok(version)
+}
+
+/// Upgrades a manifest from schema version `from` to `from + 1`.
+///
+/// Version 2 added `tags`, which needs no conversion; bumping the version keeps
+/// older binaries from dropping them when they save.
+fn migrate(table: &mut toml::Table, from: u32) {
+    if from == 0 {
+        // Version 1 replaced the single `alias` string with an `aliases` list
+        let templates = table.get_mut("templates").and_then(|t| t.as_table_mut());
+        for template in templates
+            .into_iter()
+            .flat_map(|t| t.iter_mut().map(|(_, v)| v))
+        {
+            let Some(template) = template.as_table_mut() else {
+                continue;
+            };
+            if let Some(alias) = template.remove("alias") {
+                let aliases = match alias {
+                    toml::Value::String(_) => toml::Value::Array(vec![alias]),
+                    other => other,
+                };
+                template.entry("aliases").or_insert(aliases);
+            }
+        }
+    }
+}
+
+/// Rewrites an outdated manifest in the current format after keeping a copy
+/// of the original.
+fn upgrade_file(path: &Path, version: u32, manifest: &Manifest) -> Result<()> {
+    let original = sibling(path, &format!("v{version}.bak"));
+    fs::copy(path, &original)?;
+    write_atomic(path, &toml::to_string_pretty(manifest)?)?;
+    eprintln!(
+        "{} Upgraded {} to schema version {} (original kept at {})",
+        style("ℹ️").cyan(),
+        path.display(),
+        SCHEMA_VERSION,
+        original.display()
+    );
+    Ok(())
+}
+
+/// Returns the path of the copy of the manifest taken before each save.
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling(path, "bak")
+}
+
+/// Replaces a corrupt manifest with its backup. The corrupt file is kept next
+/// to it and its path is returned.
+pub fn restore_backup(path: &Path) -> Result<PathBuf> {
+    let backup = backup_path(path);
+    let content = fs::read_to_string(&backup)
+        .map_err(|e| eyre!("Cannot read backup {}: {}", backup.display(), e))?;
+    Manifest::from_toml(&content)
+        .map_err(|e| eyre!("Backup {} is also invalid: {}", backup.display(), e))?;
+
+    let _lock = acquire_lock(path)?;
+    let corrupt = sibling(path, "corrupt");
+    if path.exists() {
+        fs::rename(path, &corrupt)?;
+    }
+    write_atomic(path, &content)?;
+    Ok(corrupt)
+}
+
+fn corrupt_manifest_error(path: &Path, error: &toml::de::Error) -> color_eyre::Report {
+    let backup = backup_path(path);
+    let recovery = if backup.exists() {
+        format!(
+            "Run 'ogito doctor --fix' to restore the last good version from {}.",
+            backup.display()
+        )
+    } else {
+        format!(
+            "Fix the file by hand, or move it aside to start over with an empty manifest:\n  mv {} {}",
+            path.display(),
+            sibling(path, "corrupt").display()
+        )
+    };
+    eyre!(
+        "The manifest {} is corrupt: {}\n{}",
+        path.display(),
+        error.message(),
+        recovery
+    )
+}
+
+fn acquire_lock(path: &Path) -> Result<File> {
+    let lock_path = sibling(path, "lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| eyre!("Cannot open lock file {}: {}", lock_path.display(), e))?;
+
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            eprintln!(
+                "{} Waiting for another ogito process to release {}...",
+                style("⏳").yellow(),
+                path.display()
+            );
+            file.lock()?;
+        }
+        Err(TryLockError::Error(e)) => {
+            return Err(eyre!("Cannot lock {}: {}", lock_path.display(), e));
+        }
+    }
+    Ok(file)
+}
+
+/// Writes `content` to a temporary file next to `path` and renames it over
+/// `path`, so the file is never left half-written.
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let temp = sibling(path, &format!("{}.tmp", std::process::id()));
+    let result = (|| -> Result<()> {
+        let mut file = File::create(&temp)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp, path)?;
         Ok(())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
     }
+    result
+}
+
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Template {
     pub description: Option<String>,
-    pub alias: Option<String>,
+    /// Alternative names; also read from the single `alias` key of older files.
+    #[serde(
+        default,
+        alias = "alias",
+        deserialize_with = "string_or_vec",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub aliases: Vec<String>,
     pub url: String,
+    /// Branch or tag the template is pinned to.
+    #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Whether a local template is read live from `url` instead of a snapshot.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub link: bool,
+    /// Name of the source project, replaced by `{{project_name}}` when snapshotting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_name: Option<String>,
+    /// Labels used to filter templates; the first one is the template's category.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Normalizes tags to lowercase, dropping duplicates while keeping their order.
+pub fn normalize_tags<'a>(tags: impl IntoIterator<Item = &'a String>) -> Result<Vec<String>> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() || tag.contains(|c: char| c.is_whitespace() || c == ',') {
+            return Err(eyre!(
+                "Invalid tag '{}'. Tags cannot be empty or contain spaces or commas",
+                tag
+            ));
+        }
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    Ok(normalized)
+}
+
+fn string_or_vec<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrVec {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match StringOrVec::deserialize(deserializer)? {
+        StringOrVec::One(alias) => vec![alias],
+        StringOrVec::Many(aliases) => aliases,
+    })
+}
+
+impl Template {
+    /// Whether the template source is a local directory rather than a remote URL.
+    pub fn is_local(&self) -> bool {
+        Path::new(&self.url).is_absolute()
+    }
+
+    /// Returns the category the template is grouped under in pickers.
+    pub fn category(&self) -> Option<&str> {
+        self.tags.first().map(String::as_str)
+    }
+
+    /// Whether the template carries every one of `tags`.
+    pub fn has_tags(&self, tags: &[String]) -> bool {
+        tags.iter().all(|tag| self.tags.contains(tag))
+    }
+
+    /// Returns the directory `ogito new` copies the template from.
+    pub fn source_dir(&self, name: &str, templates_dir: &Path) -> PathBuf {
+        if self.link {
+            PathBuf::from(&self.url)
+        } else {
+            templates_dir.join(name)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -104,8 +495,12 @@ mod tests {
         let mut config = Manifest::default();
         let template = Template {
             description: Some("A test template".to_string()),
-            alias: Some("test".to_string()),
+            aliases: vec!["test".to_string()],
             url: "https://example.com/test.git".to_string(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags: Vec::new(),
         };
         config.add_template("my-template".to_string(), template.clone());
         assert_eq!(config.templates.len(), 1);
@@ -118,8 +513,12 @@ mod tests {
         let mut config = Manifest::default();
         let template = Template {
             description: Some("A test template".to_string()),
-            alias: Some("test-alias".to_string()),
+            aliases: vec!["test-alias".to_string()],
             url: "https://example.com/test.git".to_string(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags: Vec::new(),
         };
         config.add_template("my-template".to_string(), template.clone());
         assert_eq!(config.templates.len(), 1);
@@ -135,8 +534,12 @@ mod tests {
         let mut config = Manifest::default();
         let template = Template {
             description: Some("A test template".to_string()),
-            alias: Some("test-alias".to_string()),
+            aliases: vec!["test-alias".to_string()],
             url: "https://example.com/test.git".to_string(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags: Vec::new(),
         };
         config.add_template("my-template".to_string(), template.clone());
         assert_eq!(config.templates.len(), 1);
@@ -152,8 +555,12 @@ mod tests {
         let mut config = Manifest::default();
         let template = Template {
             description: Some("A test template".to_string()),
-            alias: Some("test-alias".to_string()),
+            aliases: vec!["test-alias".to_string()],
             url: "https://example.com/test.git".to_string(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags: Vec::new(),
         };
         config.add_template("my-template".to_string(), template);
         assert_eq!(config.templates.len(), 1);
@@ -168,13 +575,21 @@ mod tests {
         let mut config = Manifest::default();
         let template1 = Template {
             description: Some("Template 1".to_string()),
-            alias: Some("shared-alias".to_string()),
+            aliases: vec!["shared-alias".to_string()],
             url: "https://example.com/template1.git".to_string(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags: Vec::new(),
         };
         let template2 = Template {
             description: Some("Template 2".to_string()),
-            alias: None,
+            aliases: Vec::new(),
             url: "https://example.com/template2.git".to_string(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags: Vec::new(),
         };
 
         config.add_template("shared-alias".to_string(), template1.clone());
@@ -187,4 +602,219 @@ mod tests {
         assert_eq!(removed.unwrap(), template1);
         assert_eq!(config.templates.len(), 1);
     }
+
+    #[test]
+    fn test_source_dir_of_linked_template() {
+        let templates_dir = std::env::temp_dir().join("templates");
+        let local_dir = std::env::temp_dir().join("my-template");
+        let mut template = Template {
+            description: None,
+            aliases: Vec::new(),
+            url: local_dir.to_string_lossy().into_owned(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags: Vec::new(),
+        };
+        assert!(template.is_local());
+        assert_eq!(
+            template.source_dir("mine", &templates_dir),
+            templates_dir.join("mine")
+        );
+
+        template.link = true;
+        assert_eq!(template.source_dir("mine", &templates_dir), local_dir);
+    }
+
+    #[test]
+    fn test_check_conflicts() {
+        let mut config = Manifest::default();
+        config.add_template(
+            "react".to_string(),
+            Template {
+                description: None,
+                aliases: vec!["r".to_string()],
+                url: "https://example.com/react.git".to_string(),
+                git_ref: None,
+                link: false,
+                project_name: None,
+                tags: Vec::new(),
+            },
+        );
+        let mut template = Template {
+            description: None,
+            aliases: Vec::new(),
+            url: "https://example.com/vue.git".to_string(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags: Vec::new(),
+        };
+
+        assert!(config.check_conflicts("vue", &template).is_ok());
+        assert!(config.check_conflicts("r", &template).is_err());
+
+        template.aliases = vec!["react".to_string()];
+        assert!(config.check_conflicts("vue", &template).is_err());
+
+        template.aliases = vec!["r".to_string()];
+        assert!(config.check_conflicts("vue", &template).is_err());
+        // An entry never conflicts with itself
+        assert!(config.check_conflicts("react", &template).is_ok());
+
+        template.aliases = vec!["vue".to_string()];
+        assert!(config.check_conflicts("vue", &template).is_err());
+    }
+
+    #[test]
+    fn test_rename_template() {
+        let mut config = Manifest::default();
+        let template = Template {
+            description: None,
+            aliases: vec!["v".to_string()],
+            url: "https://example.com/vue.git".to_string(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags: Vec::new(),
+        };
+        config.add_template("vue".to_string(), template.clone());
+        config.add_template(
+            "react".to_string(),
+            Template {
+                aliases: Vec::new(),
+                ..template.clone()
+            },
+        );
+
+        assert!(config.rename_template("vue", "react").is_err());
+        assert!(config.rename_template("missing", "other").is_err());
+        assert!(config.rename_template("vue", "vue3").is_ok());
+        assert_eq!(config.templates.get("vue3"), Some(&template));
+        assert!(!config.templates.contains_key("vue"));
+    }
+
+    #[test]
+    fn test_deserialize_single_and_multiple_aliases() {
+        let legacy: Manifest = toml::from_str(
+            r#"
+[templates.react]
+alias = "r"
+url = "https://example.com/react.git"
+"#,
+        )
+        .unwrap();
+        assert_eq!(legacy.templates["react"].aliases, vec!["r".to_string()]);
+
+        let current: Manifest = toml::from_str(
+            r#"
+[templates.react]
+aliases = ["r", "re"]
+url = "https://example.com/react.git"
+"#,
+        )
+        .unwrap();
+        assert_eq!(current.find(&"re".to_string()), Some(&"react".to_string()));
+
+        let serialized = toml::to_string_pretty(&current).unwrap();
+        assert!(serialized.contains("aliases = ["));
+    }
+
+    #[test]
+    fn test_save_keeps_backup_and_recovers_from_corruption() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("template.toml");
+
+        let mut config = ManifestFile::load(&path)?;
+        config.add_template(
+            "react".to_string(),
+            Template {
+                description: None,
+                aliases: Vec::new(),
+                url: "https://example.com/react.git".to_string(),
+                git_ref: None,
+                link: false,
+                project_name: None,
+                tags: Vec::new(),
+            },
+        );
+        config.save()?;
+        config.save()?;
+        drop(config);
+
+        fs::write(&path, "[templates.react\nurl = ")?;
+        let error = ManifestFile::load(&path).err().unwrap().to_string();
+        assert!(error.contains("is corrupt"));
+        assert!(error.contains("ogito doctor --fix"));
+
+        let corrupt = restore_backup(&path)?;
+        assert!(corrupt.exists());
+        assert!(Manifest::read(&path)?.templates.contains_key("react"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lock_is_held_until_drop() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("template.toml");
+
+        let config = ManifestFile::load(&path)?;
+        let other = File::options().write(true).open(sibling(&path, "lock"))?;
+        assert!(other.try_lock().is_err());
+
+        drop(config);
+        assert!(other.try_lock().is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_migrates_legacy_manifest() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("template.toml");
+        let legacy = "[templates.react]\nalias = \"r\"\nurl = \"https://example.com/react.git\"\n";
+        fs::write(&path, legacy)?;
+
+        let config = ManifestFile::load(&path)?;
+        assert_eq!(config.content.schema_version, SCHEMA_VERSION);
+        assert_eq!(config.content.templates["react"].aliases, vec!["r"]);
+        drop(config);
+
+        assert_eq!(fs::read_to_string(sibling(&path, "v0.bak"))?, legacy);
+        let upgraded = fs::read_to_string(&path)?;
+        assert!(upgraded.starts_with(&format!("schema_version = {SCHEMA_VERSION}")));
+        assert!(upgraded.contains("aliases = ["));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_future_schema_version() {
+        let content = format!("schema_version = {}\n[templates]\n", SCHEMA_VERSION + 1);
+        let error = Manifest::from_toml(&content).unwrap_err().to_string();
+        assert!(error.contains("Please upgrade ogito"));
+        assert!(check_schema_version(Some(-1)).is_err());
+    }
+
+    #[test]
+    fn test_tags() -> Result<()> {
+        let raw = ["Rust", "cli", "rust"].map(String::from);
+        let tags = normalize_tags(&raw)?;
+        assert_eq!(tags, vec!["rust", "cli"]);
+        assert!(normalize_tags(&["two words".to_string()]).is_err());
+        assert!(normalize_tags(&["a,b".to_string()]).is_err());
+
+        let template = Template {
+            description: None,
+            aliases: Vec::new(),
+            url: "https://example.com/cli.git".to_string(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags,
+        };
+        assert_eq!(template.category(), Some("rust"));
+        assert!(template.has_tags(&["cli".to_string()]));
+        assert!(template.has_tags(&[]));
+        assert!(!template.has_tags(&["rust".to_string(), "frontend".to_string()]));
+        Ok(())
+    }
 }
@@ -0,0 +1,313 @@
+use crate::fetch::network::Network;
+use crate::file::cache::get_cache_root;
+use crate::manifest::{Manifest, Template};
+use crate::regex::is_valid_url;
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use git2::build::RepoBuilder;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// Default time-to-live of a cached registry index, in seconds.
+pub const DEFAULT_TTL: u64 = 3600;
+
+/// How the index of a registry is fetched.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistryKind {
+    /// Cloned from a git repository with a `template.toml` at its root.
+    Git,
+    /// Downloaded from a URL serving the `template.toml` itself.
+    Http,
+}
+
+impl RegistryKind {
+    pub const NAMES: [&'static str; 2] = ["git", "http"];
+
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "git" => Ok(Self::Git),
+            "http" => Ok(Self::Http),
+            _ => Err(eyre!("Invalid registry type: '{}'", name)),
+        }
+    }
+}
+
+/// A remote, read-only source of templates shared by a team.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Registry {
+    /// A git repository containing a `template.toml`, or an HTTP URL serving one.
+    pub url: String,
+    /// How the index is fetched; without it, URLs ending in `.git` are cloned.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<RegistryKind>,
+    /// Seconds before the cached index is fetched again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u64>,
+}
+
+impl Registry {
+    fn is_git(&self) -> bool {
+        match self.kind {
+            Some(kind) => kind == RegistryKind::Git,
+            None => self.url.ends_with(".git"),
+        }
+    }
+
+    fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl.unwrap_or(DEFAULT_TTL))
+    }
+}
+
+/// Returns the namespaced name of a template provided by a registry.
+pub fn qualified_name(registry: &str, name: &str) -> String {
+    format!("@{registry}/{name}")
+}
+
+pub fn is_valid_registry_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn get_registries_dir() -> Result<PathBuf> {
+    Ok(get_cache_root()?.join("registries"))
+}
+
+fn index_path(name: &str) -> Result<PathBuf> {
+    Ok(get_registries_dir()?.join(format!("{name}.toml")))
+}
+
+fn is_fresh(path: &Path, ttl: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|md| md.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < ttl)
+}
+
+/// Downloads the index of a registry and stores it in the cache.
+pub async fn sync(name: &str, registry: &Registry) -> Result<Manifest> {
+    let content = if registry.is_git() {
+        fetch_git_index(name, &registry.url)?
+    } else {
+        let response = Network::from_settings()?.get(&registry.url).await?;
+        if !response.status().is_success() {
+            return Err(eyre!(
+                "Download failed with status {}: {}",
+                response.status(),
+                registry.url
+            ));
+        }
+        response.text().await?
+    };
+
+    let index = Manifest::from_toml(&content)
+        .map_err(|e| eyre!("Invalid index for registry '{}': {}", name, e))?;
+
+    fs::create_dir_all(get_registries_dir()?)?;
+    fs::write(index_path(name)?, content)?;
+    Ok(index)
+}
+
+fn fetch_git_index(name: &str, url: &str) -> Result<String> {
+    let checkout = get_registries_dir()?.join(format!(".{name}-checkout"));
+    if checkout.exists() {
+        fs::remove_dir_all(&checkout)?;
+    }
+
+    let repo = RepoBuilder::new()
+        .fetch_options(Network::from_settings()?.fetch_options(url))
+        .clone(url, &checkout)
+        .map_err(|e| eyre!("Failed to clone registry '{}': {}", name, e))?;
+    drop(repo);
+
+    let content = fs::read_to_string(checkout.join("template.toml"));
+    fs::remove_dir_all(&checkout)?;
+    content.map_err(|e| eyre!("Registry '{}' has no readable template.toml: {}", name, e))
+}
+
+/// Returns the index of a registry, refreshing the cache once its TTL expires.
+/// A stale cache is used if the registry cannot be reached.
+pub async fn load_index(name: &str, registry: &Registry) -> Result<Manifest> {
+    let path = index_path(name)?;
+    if is_fresh(&path, registry.ttl()) {
+        let content = fs::read_to_string(&path)?;
+        return Manifest::from_toml(&content);
+    }
+
+    match sync(name, registry).await {
+        Ok(index) => Ok(index),
+        Err(e) if path.exists() => {
+            eprintln!(
+                "{} Using cached index for registry '{}': {}",
+                style("⚠️").yellow(),
+                name,
+                e
+            );
+            let content = fs::read_to_string(&path)?;
+            Manifest::from_toml(&content)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Returns a copy of `manifest` that also contains the templates of every
+/// configured registry, namespaced as `@<registry>/<name>`.
+pub async fn merged(manifest: &Manifest) -> Result<Manifest> {
+    let mut merged = manifest.clone();
+    for (registry_name, registry) in &manifest.registries {
+        let index = match load_index(registry_name, registry).await {
+            Ok(index) => index,
+            Err(e) => {
+                eprintln!(
+                    "{} Skipping registry '{}': {}",
+                    style("⚠️").yellow(),
+                    registry_name,
+                    e
+                );
+                continue;
+            }
+        };
+        merge_index(&mut merged, registry_name, index);
+    }
+    Ok(merged)
+}
+
+/// Adds the templates of a registry's index to `manifest`. Entries pointing
+/// at local directories are skipped, since they would read the user's disk.
+fn merge_index(manifest: &mut Manifest, registry_name: &str, index: Manifest) {
+    for (name, template) in index.templates {
+        if name.contains("..") || Path::new(&name).is_absolute() {
+            continue;
+        }
+        if template.link || !is_valid_url(&template.url).unwrap_or(false) {
+            eprintln!(
+                "{} Skipping '{}' from registry '{}': its source is not a repository URL",
+                style("⚠️").yellow(),
+                name,
+                registry_name
+            );
+            continue;
+        }
+        let template = Template {
+            aliases: template
+                .aliases
+                .iter()
+                .map(|alias| qualified_name(registry_name, alias))
+                .collect(),
+            ..template
+        };
+        manifest.add_template(qualified_name(registry_name, &name), template);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_index_namespaces_entries() {
+        let mut manifest = Manifest::default();
+        manifest.add_template(
+            "react".to_string(),
+            Template {
+                description: None,
+                aliases: vec!["r".to_string()],
+                url: "https://github.com/me/react".to_string(),
+                git_ref: None,
+                link: false,
+                project_name: None,
+                tags: Vec::new(),
+            },
+        );
+
+        let mut index = Manifest::default();
+        index.add_template(
+            "react".to_string(),
+            Template {
+                description: Some("Company React".to_string()),
+                aliases: vec!["r".to_string()],
+                url: "https://github.com/acme/react".to_string(),
+                git_ref: None,
+                link: false,
+                project_name: None,
+                tags: Vec::new(),
+            },
+        );
+        for (name, url, link) in [
+            ("linked", "https://github.com/acme/linked", true),
+            ("local", "/home/me/secrets", false),
+            ("relative", "../secrets", false),
+        ] {
+            index.add_template(
+                name.to_string(),
+                Template {
+                    description: None,
+                    aliases: Vec::new(),
+                    url: url.to_string(),
+                    git_ref: None,
+                    link,
+                    project_name: None,
+                    tags: Vec::new(),
+                },
+            );
+        }
+        index.add_template(
+            "../escape".to_string(),
+            Template {
+                description: None,
+                aliases: Vec::new(),
+                url: "https://github.com/acme/escape".to_string(),
+                git_ref: None,
+                link: false,
+                project_name: None,
+                tags: Vec::new(),
+            },
+        );
+
+        merge_index(&mut manifest, "acme", index);
+
+        assert_eq!(manifest.templates.len(), 2);
+        assert_eq!(manifest.find(&"r".to_string()), Some(&"react".to_string()));
+        assert_eq!(
+            manifest.find(&"@acme/r".to_string()),
+            Some(&"@acme/react".to_string())
+        );
+        assert_eq!(
+            manifest.templates["@acme/react"].url,
+            "https://github.com/acme/react"
+        );
+    }
+
+    #[test]
+    fn test_is_valid_registry_name() {
+        assert!(is_valid_registry_name("acme"));
+        assert!(is_valid_registry_name("acme-corp_1"));
+        assert!(!is_valid_registry_name(""));
+        assert!(!is_valid_registry_name("../acme"));
+        assert!(!is_valid_registry_name("acme/team"));
+    }
+
+    #[test]
+    fn test_is_git() {
+        let registry = |url: &str, kind| Registry {
+            url: url.to_string(),
+            kind,
+            ttl: None,
+        };
+        assert!(registry("https://github.com/acme/registry.git", None).is_git());
+        assert!(!registry(
+            "https://github.com/acme/registry/raw/main/template.toml",
+            None
+        )
+        .is_git());
+        assert!(registry("https://github.com/acme/registry", Some(RegistryKind::Git)).is_git());
+        assert!(!registry("https://example.com/index.git", Some(RegistryKind::Http)).is_git());
+    }
+}
@@ -0,0 +1,371 @@
+//! Keeps what destructive operations delete, so `ogito undo` can restore it.
+//!
+//! Every operation gets an entry directory under `<home>/trash` holding the
+//! removed files and an `entry.toml` describing where they came from, along
+//! with any manifest entries removed alongside them.
+
+use crate::file::cache::get_trash_dir;
+use crate::manifest::{Manifest, Template};
+use color_eyre::{eyre::eyre, Result};
+use console::style;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Days entries are kept when `trash.retention_days` is not set.
+pub const DEFAULT_RETENTION_DAYS: u64 = 14;
+
+const ENTRY_FILE: &str = "entry.toml";
+
+/// A file or directory moved to the trash.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashedPath {
+    pub original: PathBuf,
+    /// Name of the copy inside the entry directory.
+    pub stored: String,
+}
+
+/// What one destructive operation removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    /// The command that removed the content, such as `remove my-template`.
+    pub operation: String,
+    /// Seconds since the Unix epoch.
+    pub created: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<TrashedPath>,
+    /// Manifest entries removed by the operation.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, Template>,
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+impl TrashEntry {
+    /// Moves `path` into the entry.
+    pub fn store(&mut self, path: &Path) -> Result<()> {
+        let original = std::path::absolute(path)?;
+        let name = original
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "root".to_string());
+        let stored = format!("{}-{}", self.paths.len(), name);
+        move_path(&original, &self.dir.join(&stored))?;
+        self.paths.push(TrashedPath { original, stored });
+        // Saved after every move so an interrupted operation stays restorable
+        self.save()
+    }
+
+    /// Records a manifest entry removed by the operation.
+    pub fn store_template(&mut self, name: &str, template: Template) -> Result<()> {
+        self.templates.insert(name.to_string(), template);
+        self.save()
+    }
+
+    /// Returns the directory holding the entry.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn age(&self) -> Duration {
+        now().saturating_sub(Duration::from_secs(self.created))
+    }
+
+    fn save(&self) -> Result<()> {
+        fs::write(self.dir.join(ENTRY_FILE), toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// The trash directory.
+pub struct Trash {
+    root: PathBuf,
+}
+
+impl Trash {
+    /// Opens the trash in the ogito home.
+    pub fn open() -> Result<Self> {
+        Ok(Self::at(get_trash_dir()?))
+    }
+
+    pub fn at(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Starts an entry for `operation`.
+    pub fn create_entry(&self, operation: &str) -> Result<TrashEntry> {
+        fs::create_dir_all(&self.root)?;
+        let created = now().as_secs();
+        // Several operations can run within the same second
+        for n in 0.. {
+            let id = format!("{created}-{n:03}");
+            let dir = self.root.join(&id);
+            match fs::create_dir(&dir) {
+                Ok(()) => {
+                    let entry = TrashEntry {
+                        id,
+                        operation: operation.to_string(),
+                        created,
+                        paths: Vec::new(),
+                        templates: BTreeMap::new(),
+                        dir,
+                    };
+                    entry.save()?;
+                    return Ok(entry);
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        unreachable!()
+    }
+
+    /// Returns all entries, newest first.
+    pub fn entries(&self) -> Result<Vec<TrashEntry>> {
+        if !self.root.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut entries = Vec::new();
+        for dir in fs::read_dir(&self.root)? {
+            let dir = dir?.path();
+            let Ok(content) = fs::read_to_string(dir.join(ENTRY_FILE)) else {
+                continue;
+            };
+            let mut entry: TrashEntry = toml::from_str(&content)
+                .map_err(|e| eyre!("Invalid trash entry {}: {}", dir.display(), e))?;
+            entry.dir = dir;
+            entries.push(entry);
+        }
+        entries.sort_by(|a, b| (b.created, &b.id).cmp(&(a.created, &a.id)));
+        Ok(entries)
+    }
+
+    pub fn find(&self, id: &str) -> Result<TrashEntry> {
+        self.entries()?
+            .into_iter()
+            .find(|entry| entry.id == id)
+            .ok_or_else(|| {
+                eyre!(
+                    "No trash entry '{}'. Run 'ogito trash ls' to list them.",
+                    id
+                )
+            })
+    }
+
+    /// Moves the content of `entry` back where it came from and adds its
+    /// templates back to `manifest`. Anything now occupying those paths is
+    /// moved to a new entry rather than deleted.
+    pub fn restore(&self, entry: &TrashEntry, manifest: &mut Manifest) -> Result<()> {
+        for name in entry.templates.keys() {
+            if manifest.templates.contains_key(name) {
+                return Err(eyre!(
+                    "Cannot restore template '{}': a template with that name exists. Rename or remove it first.",
+                    name
+                ));
+            }
+        }
+
+        let mut displaced: Option<TrashEntry> = None;
+        for trashed in &entry.paths {
+            if fs::symlink_metadata(&trashed.original).is_ok() {
+                if displaced.is_none() {
+                    let operation = format!("replaced by restoring '{}'", entry.operation);
+                    displaced = Some(self.create_entry(&operation)?);
+                }
+                if let Some(displaced) = displaced.as_mut() {
+                    displaced.store(&trashed.original)?;
+                }
+            }
+            if let Some(parent) = trashed.original.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            move_path(&entry.dir.join(&trashed.stored), &trashed.original)?;
+        }
+
+        for (name, template) in &entry.templates {
+            manifest.add_template(name.clone(), template.clone());
+        }
+        fs::remove_dir_all(&entry.dir)?;
+        Ok(())
+    }
+
+    /// Deletes entries older than `age`, returning how many were deleted.
+    pub fn purge_older_than(&self, age: Duration) -> Result<usize> {
+        let mut purged = 0;
+        for entry in self.entries()? {
+            if entry.age() > age {
+                fs::remove_dir_all(&entry.dir)?;
+                purged += 1;
+            }
+        }
+        Ok(purged)
+    }
+
+    /// Deletes every entry, returning how many were deleted.
+    pub fn empty(&self) -> Result<usize> {
+        let entries = self.entries()?;
+        for entry in &entries {
+            fs::remove_dir_all(&entry.dir)?;
+        }
+        Ok(entries.len())
+    }
+}
+
+/// Moves `path` into a new entry of the trash for `operation`, deleting
+/// expired entries first.
+pub fn discard(operation: &str, path: &Path) -> Result<TrashEntry> {
+    let trash = Trash::open()?;
+    trash.purge_older_than(retention())?;
+    let mut entry = trash.create_entry(operation)?;
+    entry.store(path)?;
+    Ok(entry)
+}
+
+/// Tells the user how to get back what `entry` holds.
+pub fn print_undo_hint(entry: &TrashEntry) {
+    let items = entry.paths.len() + entry.templates.len();
+    println!(
+        "{} Moved {} item(s) to the trash. Run '{}' to restore them.",
+        style("🗑️").cyan(),
+        items,
+        style("ogito undo").bold()
+    );
+}
+
+/// Returns how long entries are kept, from `trash.retention_days`.
+pub fn retention() -> Duration {
+    let days = crate::user_config::UserConfig::load()
+        .ok()
+        .and_then(|settings| settings.resolve("trash.retention_days"))
+        .and_then(|days| days.parse().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS);
+    Duration::from_secs(days * 24 * 60 * 60)
+}
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Moves `from` to `to`, copying when they are on different file systems.
+fn move_path(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_path(from, to)?;
+    if fs::symlink_metadata(from)?.is_dir() {
+        fs::remove_dir_all(from)?;
+    } else {
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+fn copy_path(from: &Path, to: &Path) -> Result<()> {
+    let metadata = fs::symlink_metadata(from)?;
+    if metadata.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else if metadata.is_symlink() {
+        let target = fs::read_link(from)?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(target, to)?;
+        #[cfg(not(unix))]
+        fs::copy(from.parent().unwrap_or(from).join(target), to)?;
+    } else {
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn template() -> Template {
+        Template {
+            description: None,
+            aliases: Vec::new(),
+            url: "https://github.com/acme/starter".to_string(),
+            git_ref: None,
+            link: false,
+            project_name: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_store_and_restore() -> Result<()> {
+        let dir = tempdir()?;
+        let trash = Trash::at(dir.path().join("trash"));
+        let project = dir.path().join("project");
+        fs::create_dir_all(project.join("src"))?;
+        fs::write(project.join("src/main.rs"), "fn main() {}\n")?;
+
+        let mut entry = trash.create_entry("remove starter")?;
+        entry.store(&project)?;
+        entry.store_template("starter", template())?;
+        assert!(!project.exists());
+
+        let entries = trash.entries()?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].operation, "remove starter");
+        assert_eq!(entries[0].paths[0].original, project);
+
+        // Something new took the path since; it is kept rather than lost
+        fs::create_dir_all(&project)?;
+        fs::write(project.join("new.txt"), "new")?;
+
+        let mut manifest = Manifest::default();
+        trash.restore(&entries[0], &mut manifest)?;
+        assert_eq!(
+            fs::read_to_string(project.join("src/main.rs"))?,
+            "fn main() {}\n"
+        );
+        assert!(manifest.templates.contains_key("starter"));
+
+        let entries = trash.entries()?;
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].operation.starts_with("replaced by restoring"));
+        let stored = entries[0].dir().join(&entries[0].paths[0].stored);
+        assert!(stored.join("new.txt").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_refuses_template_conflicts() -> Result<()> {
+        let dir = tempdir()?;
+        let trash = Trash::at(dir.path().join("trash"));
+        let mut entry = trash.create_entry("remove starter")?;
+        entry.store_template("starter", template())?;
+
+        let mut manifest = Manifest::default();
+        manifest.add_template("starter".to_string(), template());
+        assert!(trash.restore(&entry, &mut manifest).is_err());
+        assert_eq!(trash.entries()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_purge_and_empty() -> Result<()> {
+        let dir = tempdir()?;
+        let trash = Trash::at(dir.path().join("trash"));
+        let first = trash.create_entry("new app")?;
+        let second = trash.create_entry("new app")?;
+        assert_ne!(first.id, second.id);
+
+        assert_eq!(trash.purge_older_than(Duration::from_secs(3600))?, 0);
+        assert_eq!(trash.empty()?, 2);
+        assert!(trash.entries()?.is_empty());
+        Ok(())
+    }
+}
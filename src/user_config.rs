@@ -0,0 +1,329 @@
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Keys understood by `ogito config`, with a short description.
+pub const KEYS: &[(&str, &str)] = &[
+    ("mode", "Default clone mode for `ogito new` (git or tar)"),
+    ("keep_history", "Keep the git history of cloned templates"),
+    ("branch", "Default branch to clone"),
+    (
+        "hosts",
+        "Preferred hosts for owner/repo shorthands, comma separated (github, gitlab, bitbucket or a hostname)",
+    ),
+    ("author.name", "Author name for generated projects"),
+    ("author.email", "Author email for generated projects"),
+    (
+        "git.init",
+        "Initialize a git repository in generated projects",
+    ),
+    (
+        "git.default_branch",
+        "Branch name for repositories created by `git.init`",
+    ),
+    (
+        "git.commit_message",
+        "Message of the initial commit created by `git.init`",
+    ),
+    (
+        "tokens_file",
+        "TOML file of access tokens by host, e.g. \"github.com\" = \"ghp_...\"",
+    ),
+    ("proxy", "Proxy used for network access"),
+    (
+        "archive.max_size_mb",
+        "Largest total size, in MB, extracted from a template archive",
+    ),
+    (
+        "archive.max_entries",
+        "Most files and directories extracted from a template archive",
+    ),
+    (
+        "trash.retention_days",
+        "Days deleted content is kept for `ogito undo`",
+    ),
+    ("ui.color", "Whether to use colored output"),
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct UserConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_history: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosts: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    pub author: Author,
+    pub git: GitInit,
+    pub archive: ArchiveLimits,
+    pub trash: TrashSettings,
+    pub ui: Ui,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Author {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct GitInit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub init: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ArchiveLimits {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size_mb: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TrashSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention_days: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Ui {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<bool>,
+}
+
+pub fn get_config_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| eyre!("Could not determine the user configuration directory"))?;
+    Ok(config_dir.join("ogito").join("config.toml"))
+}
+
+/// Returns the environment variable overriding `key`, e.g. `OGITO_AUTHOR_NAME`.
+pub fn env_var_name(key: &str) -> String {
+    format!("OGITO_{}", key.replace('.', "_").to_uppercase())
+}
+
+impl UserConfig {
+    /// Loads the user configuration, falling back to the built-in defaults if
+    /// the file does not exist.
+    pub fn load() -> Result<Self> {
+        Self::load_from(&get_config_path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| eyre!("Invalid config file {}: {}", path.display(), e))
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        let value = match key {
+            "mode" => self.mode.clone(),
+            "keep_history" => self.keep_history.map(|v| v.to_string()),
+            "branch" => self.branch.clone(),
+            "hosts" => self.hosts.as_ref().map(|hosts| hosts.join(",")),
+            "author.name" => self.author.name.clone(),
+            "author.email" => self.author.email.clone(),
+            "git.init" => self.git.init.map(|v| v.to_string()),
+            "git.default_branch" => self.git.default_branch.clone(),
+            "git.commit_message" => self.git.commit_message.clone(),
+            "tokens_file" => self.tokens_file.clone(),
+            "proxy" => self.proxy.clone(),
+            "archive.max_size_mb" => self.archive.max_size_mb.map(|v| v.to_string()),
+            "archive.max_entries" => self.archive.max_entries.map(|v| v.to_string()),
+            "trash.retention_days" => self.trash.retention_days.map(|v| v.to_string()),
+            "ui.color" => self.ui.color.map(|v| v.to_string()),
+            _ => return Err(unknown_key(key)),
+        };
+        Ok(value)
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let value = value.trim().to_string();
+        match key {
+            "mode" => {
+                if value != "git" && value != "tar" {
+                    return Err(eyre!("Invalid mode '{}': expected 'git' or 'tar'", value));
+                }
+                self.mode = Some(value);
+            }
+            "keep_history" => self.keep_history = Some(parse_bool(key, &value)?),
+            "branch" => self.branch = Some(value),
+            "hosts" => {
+                self.hosts = Some(
+                    value
+                        .split(',')
+                        .map(|host| host.trim().to_lowercase())
+                        .filter(|host| !host.is_empty())
+                        .collect(),
+                )
+            }
+            "author.name" => self.author.name = Some(value),
+            "author.email" => self.author.email = Some(value),
+            "git.init" => self.git.init = Some(parse_bool(key, &value)?),
+            "git.default_branch" => {
+                if !matches!(git2::Branch::name_is_valid(&value), Ok(true)) {
+                    return Err(eyre!("Invalid branch name '{}'", value));
+                }
+                self.git.default_branch = Some(value);
+            }
+            "git.commit_message" => self.git.commit_message = Some(value),
+            "tokens_file" => self.tokens_file = Some(value),
+            "proxy" => self.proxy = Some(value),
+            "archive.max_size_mb" => {
+                self.archive.max_size_mb = Some(parse_number(key, &value, "megabytes")?)
+            }
+            "archive.max_entries" => {
+                self.archive.max_entries = Some(parse_number(key, &value, "entries")?)
+            }
+            "trash.retention_days" => {
+                self.trash.retention_days = Some(parse_number(key, &value, "days")?)
+            }
+            "ui.color" => self.ui.color = Some(parse_bool(key, &value)?),
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+
+    pub fn unset(&mut self, key: &str) -> Result<()> {
+        match key {
+            "mode" => self.mode = None,
+            "keep_history" => self.keep_history = None,
+            "branch" => self.branch = None,
+            "hosts" => self.hosts = None,
+            "author.name" => self.author.name = None,
+            "author.email" => self.author.email = None,
+            "git.init" => self.git.init = None,
+            "git.default_branch" => self.git.default_branch = None,
+            "git.commit_message" => self.git.commit_message = None,
+            "tokens_file" => self.tokens_file = None,
+            "proxy" => self.proxy = None,
+            "archive.max_size_mb" => self.archive.max_size_mb = None,
+            "archive.max_entries" => self.archive.max_entries = None,
+            "trash.retention_days" => self.trash.retention_days = None,
+            "ui.color" => self.ui.color = None,
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+
+    /// Resolves `key` from its environment variable, then from the config file.
+    /// CLI flags and built-in defaults are handled by the caller.
+    pub fn resolve(&self, key: &str) -> Option<String> {
+        std::env::var(env_var_name(key))
+            .ok()
+            .filter(|value| !value.is_empty())
+            .or_else(|| self.get(key).ok().flatten())
+    }
+
+    pub fn resolve_bool(&self, key: &str) -> Option<bool> {
+        self.resolve(key)
+            .and_then(|value| parse_bool(key, &value).ok())
+    }
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(eyre!(
+            "Invalid value '{}' for '{}': expected true or false",
+            value,
+            key
+        )),
+    }
+}
+
+fn parse_number(key: &str, value: &str, unit: &str) -> Result<u64> {
+    value.parse().map_err(|_| {
+        eyre!(
+            "Invalid value '{}' for '{}': expected a number of {}",
+            value,
+            key,
+            unit
+        )
+    })
+}
+
+fn unknown_key(key: &str) -> color_eyre::Report {
+    eyre!(
+        "Unknown config key '{}'. Run 'ogito config list' to see available keys.",
+        key
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_set_get_roundtrip() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("ogito").join("config.toml");
+
+        let mut config = UserConfig::default();
+        config.set("mode", "tar")?;
+        config.set("keep_history", "yes")?;
+        config.set("hosts", "GitHub, gitlab")?;
+        config.set("author.name", "Jane Doe")?;
+        config.save_to(&path)?;
+
+        let loaded = UserConfig::load_from(&path)?;
+        assert_eq!(loaded, config);
+        assert_eq!(loaded.get("mode")?, Some("tar".to_string()));
+        assert_eq!(loaded.get("keep_history")?, Some("true".to_string()));
+        assert_eq!(loaded.get("hosts")?, Some("github,gitlab".to_string()));
+        assert_eq!(loaded.get("author.email")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_values_and_keys() {
+        let mut config = UserConfig::default();
+        assert!(config.set("mode", "zip").is_err());
+        assert!(config.set("ui.color", "maybe").is_err());
+        assert!(config.set("git.default_branch", "bad..name").is_err());
+        assert!(config.set("archive.max_entries", "lots").is_err());
+        assert!(config.set("nope", "value").is_err());
+        assert!(config.get("nope").is_err());
+    }
+
+    #[test]
+    fn test_env_var_name() {
+        assert_eq!(env_var_name("mode"), "OGITO_MODE");
+        assert_eq!(env_var_name("author.email"), "OGITO_AUTHOR_EMAIL");
+    }
+}